@@ -0,0 +1,348 @@
+//! Discovery of the source of a Cargo target from the `Cargo.toml` manifest.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::LineColumn;
+use syn::Item;
+
+use crate::charset;
+use crate::cli::InputCharset;
+use crate::parser::is_test_module;
+
+/// Determines the entry point of the library target of the manifest.
+///
+/// The path is taken from the `[lib]` section and defaults to `src/lib.rs`.
+pub fn lib_entry_point(manifest_path: &Path) -> Result<PathBuf, String> {
+    let manifest = read_manifest(manifest_path)?;
+    let manifest_dir = manifest_path.parent().unwrap_or(Path::new(""));
+    let path = manifest
+        .get("lib")
+        .and_then(|lib| lib.get("path"))
+        .and_then(|path| path.as_str())
+        .unwrap_or("src/lib.rs");
+    Ok(manifest_dir.join(path))
+}
+
+/// Determines the entry point of the binary target with the given name.
+///
+/// The path is taken from the matching `[[bin]]` section. Without one, the binary
+/// named like the package is `src/main.rs`, all others are `src/bin/<name>.rs`.
+pub fn bin_entry_point(manifest_path: &Path, name: &str) -> Result<PathBuf, String> {
+    let manifest = read_manifest(manifest_path)?;
+    let manifest_dir = manifest_path.parent().unwrap_or(Path::new(""));
+    let bin_section = manifest
+        .get("bin")
+        .and_then(|bins| bins.as_array())
+        .and_then(|bins| {
+            bins.iter()
+                .find(|bin| bin.get("name").and_then(|n| n.as_str()) == Some(name))
+        });
+    if let Some(path) = bin_section
+        .and_then(|bin| bin.get("path"))
+        .and_then(|path| path.as_str())
+    {
+        return Ok(manifest_dir.join(path));
+    }
+
+    let package_name = manifest
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(|package_name| package_name.as_str());
+    if package_name == Some(name) {
+        return Ok(manifest_dir.join("src/main.rs"));
+    }
+    let path = manifest_dir.join(format!("src/bin/{name}.rs"));
+    if bin_section.is_some() || path.exists() {
+        Ok(path)
+    } else {
+        Err(format!("No binary target named '{name}'"))
+    }
+}
+
+/// Reads the entry point and all files of its module tree into one source.
+///
+/// Every `mod name;` declaration is resolved to `name.rs` or `name/mod.rs` and
+/// replaced by the content of the file as inline module `mod name { ... }`, so the
+/// module structure is kept for the documentation and the diagrams.
+///
+/// `#[cfg(test)]` modules are skipped unless `include_tests` is set. All files
+/// are decoded in the given charset.
+pub fn read_module_tree(
+    entry_point: &Path,
+    include_tests: bool,
+    charset: &InputCharset,
+) -> Result<String, String> {
+    let source = read_source(entry_point, charset)?;
+    let parsed_file = syn::parse_file(&source)
+        .map_err(|e| format!("Failed to parse '{}': {e}", entry_point.display()))?;
+
+    let module_dir = module_dir(entry_point);
+
+    let mut module_tree = source.clone();
+    // replaced from the end, so the offsets of the declarations before stay valid
+    for item in parsed_file.items.iter().rev() {
+        let Item::Mod(item_mod) = item else {
+            continue;
+        };
+        let Some(semicolon) = &item_mod.semi else {
+            continue;
+        };
+        if is_test_module(item_mod) && !include_tests {
+            continue;
+        }
+        let name = item_mod.ident.to_string();
+        let module_path = module_file(&module_dir, &name);
+        let module_source = read_module_tree(&module_path, include_tests, charset)?;
+        let offset = byte_offset(&source, semicolon.span.start());
+        module_tree.replace_range(offset..offset + 1, &format!(" {{\n{module_source}\n}}"));
+    }
+    Ok(module_tree)
+}
+
+/// Converts the line and character column of a span to the byte offset in the source.
+fn byte_offset(source: &str, location: LineColumn) -> usize {
+    let line_start: usize = source
+        .split_inclusive('\n')
+        .take(location.line - 1)
+        .map(str::len)
+        .sum();
+    let column: usize = source[line_start..]
+        .chars()
+        .take(location.column)
+        .map(char::len_utf8)
+        .sum();
+    line_start + column
+}
+
+/// Renders an outline of the module tree of the entry point as nested AsciiDoc
+/// list. Each module is listed with the file it is read from, inline modules are
+/// marked as such.
+///
+/// `#[cfg(test)]` modules are skipped unless `include_tests` is set. All files
+/// are decoded in the given charset.
+pub fn render_module_outline(
+    entry_point: &Path,
+    include_tests: bool,
+    charset: &InputCharset,
+) -> Result<String, String> {
+    let mut outline = format!("= Module Tree\n\n* `crate` ({})\n", entry_point.display());
+    collect_module_outline(entry_point, include_tests, charset, 2, &mut outline)?;
+    Ok(outline)
+}
+
+/// Appends the modules declared in the file to the outline, at the given list depth.
+fn collect_module_outline(
+    file_path: &Path,
+    include_tests: bool,
+    charset: &InputCharset,
+    depth: usize,
+    outline: &mut String,
+) -> Result<(), String> {
+    let source = read_source(file_path, charset)?;
+    let parsed_file = syn::parse_file(&source)
+        .map_err(|e| format!("Failed to parse '{}': {e}", file_path.display()))?;
+    let module_dir = module_dir(file_path);
+    append_modules(
+        &parsed_file.items,
+        &module_dir,
+        include_tests,
+        charset,
+        depth,
+        outline,
+    )
+}
+
+/// Appends the modules among the items to the outline. The files of modules
+/// declared with `mod name;` are resolved relative to the module directory.
+fn append_modules(
+    items: &[Item],
+    module_dir: &Path,
+    include_tests: bool,
+    charset: &InputCharset,
+    depth: usize,
+    outline: &mut String,
+) -> Result<(), String> {
+    for item in items {
+        let Item::Mod(item_mod) = item else {
+            continue;
+        };
+        if is_test_module(item_mod) && !include_tests {
+            continue;
+        }
+        let name = item_mod.ident.to_string();
+        let bullet = "*".repeat(depth);
+        match &item_mod.content {
+            Some((_, mod_items)) => {
+                outline.push_str(&format!("{bullet} `{name}` (inline)\n"));
+                let inline_dir = module_dir.join(&name);
+                append_modules(
+                    mod_items,
+                    &inline_dir,
+                    include_tests,
+                    charset,
+                    depth + 1,
+                    outline,
+                )?;
+            }
+            None => {
+                let module_path = module_file(module_dir, &name);
+                outline.push_str(&format!("{bullet} `{name}` ({})\n", module_path.display()));
+                collect_module_outline(&module_path, include_tests, charset, depth + 1, outline)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads a source file of the crate and decodes it in the charset.
+fn read_source(file_path: &Path, charset: &InputCharset) -> Result<String, String> {
+    let bytes = fs::read(file_path)
+        .map_err(|e| format!("Failed to read '{}': {e}", file_path.display()))?;
+    charset::decode(&bytes, charset)
+        .map_err(|e| format!("Failed to read '{}': {e}", file_path.display()))
+}
+
+/// Determines the directory the files of the modules declared in the file are in.
+///
+/// This is the directory of the file for `lib.rs`, `main.rs`, `mod.rs` and
+/// binaries in `src/bin`, otherwise the directory named like the file.
+fn module_dir(file_path: &Path) -> PathBuf {
+    let is_mod_root = matches!(
+        file_path.file_name().and_then(|name| name.to_str()),
+        Some("lib.rs" | "main.rs" | "mod.rs")
+    ) || file_path.parent().and_then(|dir| dir.file_name())
+        == Some("bin".as_ref());
+    if is_mod_root {
+        file_path.parent().unwrap_or(Path::new("")).to_path_buf()
+    } else {
+        file_path.with_extension("")
+    }
+}
+
+/// Resolves the module `name` to `name.rs` or, if that doesn't exist, `name/mod.rs`.
+fn module_file(module_dir: &Path, name: &str) -> PathBuf {
+    let file_path = module_dir.join(format!("{name}.rs"));
+    if file_path.exists() {
+        file_path
+    } else {
+        module_dir.join(name).join("mod.rs")
+    }
+}
+
+/// Reads and parses the manifest.
+fn read_manifest(manifest_path: &Path) -> Result<toml::Table, String> {
+    let content = fs::read_to_string(manifest_path)
+        .map_err(|e| format!("Failed to read '{}': {e}", manifest_path.display()))?;
+    content
+        .parse::<toml::Table>()
+        .map_err(|e| format!("Failed to parse '{}': {e}", manifest_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a crate of the given files in a directory of its own below the
+    /// temporary directory and returns the directory.
+    fn create_crate(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let crate_dir =
+            std::env::temp_dir().join(format!("rustitect_{name}_{}", std::process::id()));
+        for (path, content) in files {
+            let file_path = crate_dir.join(path);
+            fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            fs::write(file_path, content).unwrap();
+        }
+        crate_dir
+    }
+
+    /// The files of a crate with a library and a binary and nested modules.
+    const CRATE_FILES: &[(&str, &str)] = &[
+        (
+            "Cargo.toml",
+            "[package]\nname = \"shop\"\n\n[[bin]]\nname = \"admin\"\npath = \"tools/admin.rs\"\n",
+        ),
+        (
+            "src/lib.rs",
+            "/// The shop.\npub struct Shop {}\n\npub mod model;\n\n#[cfg(test)]\nmod tests;\n",
+        ),
+        ("src/model.rs", "pub struct Order {}\n\nmod item;\n"),
+        ("src/model/item.rs", "pub struct Item {}\n"),
+        ("src/tests.rs", "struct Fixture {}\n"),
+    ];
+
+    #[test]
+    fn test_entry_points_of_the_manifest() {
+        let crate_dir = create_crate("entry_points", CRATE_FILES);
+        let manifest_path = crate_dir.join("Cargo.toml");
+
+        let lib = lib_entry_point(&manifest_path).unwrap();
+        let main_bin = bin_entry_point(&manifest_path, "shop").unwrap();
+        let admin_bin = bin_entry_point(&manifest_path, "admin").unwrap();
+
+        assert_eq!(lib, crate_dir.join("src/lib.rs"));
+        assert_eq!(main_bin, crate_dir.join("src/main.rs"));
+        assert_eq!(admin_bin, crate_dir.join("tools/admin.rs"));
+        assert!(bin_entry_point(&manifest_path, "unknown").is_err());
+
+        fs::remove_dir_all(crate_dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_module_tree_inlines_modules() {
+        let crate_dir = create_crate("module_tree", CRATE_FILES);
+
+        let module_tree =
+            read_module_tree(&crate_dir.join("src/lib.rs"), false, &InputCharset::Utf8).unwrap();
+
+        assert_eq!(
+            module_tree,
+            "/// The shop.\npub struct Shop {}\n\npub mod model {\npub struct Order {}\n\n\
+            mod item {\npub struct Item {}\n\n}\n\n}\n\n#[cfg(test)]\nmod tests;\n"
+        );
+        assert!(syn::parse_file(&module_tree).is_ok());
+
+        fs::remove_dir_all(crate_dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_module_tree_decodes_the_charset() {
+        let crate_dir = create_crate("module_tree_charset", CRATE_FILES);
+        let lib_path = crate_dir.join("src/lib.rs");
+        fs::write(
+            crate_dir.join("src/model/item.rs"),
+            b"/// Gr\xfc\xdfe\nstruct Item {}\n",
+        )
+        .unwrap();
+
+        let module_tree = read_module_tree(&lib_path, false, &InputCharset::Latin1).unwrap();
+
+        assert!(module_tree.contains("mod item {\n/// Grüße\nstruct Item {}\n"));
+        assert!(read_module_tree(&lib_path, false, &InputCharset::Utf8)
+            .unwrap_err()
+            .contains("item.rs"));
+
+        fs::remove_dir_all(crate_dir).unwrap();
+    }
+
+    #[test]
+    fn test_render_module_outline_lists_files() {
+        let crate_dir = create_crate("module_outline", CRATE_FILES);
+        let src_dir = crate_dir.join("src");
+
+        let outline =
+            render_module_outline(&src_dir.join("lib.rs"), false, &InputCharset::Utf8).unwrap();
+
+        assert_eq!(
+            outline,
+            format!(
+                "= Module Tree\n\n* `crate` ({})\n** `model` ({})\n*** `item` ({})\n",
+                src_dir.join("lib.rs").display(),
+                src_dir.join("model.rs").display(),
+                src_dir.join("model").join("item.rs").display()
+            )
+        );
+
+        fs::remove_dir_all(crate_dir).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+//! Decoding of input files which are not encoded in UTF-8.
+
+use crate::cli::InputCharset;
+
+/// Decodes the bytes of an input file in the given charset.
+///
+/// # Returns
+///
+/// The decoded text, or an error if the bytes are no valid UTF-8. The single byte
+/// charsets decode every byte.
+pub fn decode(bytes: &[u8], charset: &InputCharset) -> Result<String, String> {
+    match charset {
+        InputCharset::Utf8 => String::from_utf8(bytes.to_vec()).map_err(|e| {
+            format!("The input is no valid UTF-8 ({e}), see '--input-charset' for other encodings")
+        }),
+        // the WHATWG label 'iso-8859-1' is Windows-1252, so Latin-1 is decoded on its own
+        InputCharset::Latin1 => Ok(encoding_rs::mem::decode_latin1(bytes).into_owned()),
+        InputCharset::Windows1252 => {
+            let (text, _) = encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes);
+            Ok(text.into_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_latin1_doc_comment() {
+        let bytes = b"/// Gr\xfc\xdfe an Jos\xe9\nstruct Greeting {}\n";
+
+        assert_eq!(
+            decode(bytes, &InputCharset::Latin1).unwrap(),
+            "/// Grüße an José\nstruct Greeting {}\n"
+        );
+        assert!(decode(bytes, &InputCharset::Utf8)
+            .unwrap_err()
+            .contains("--input-charset"));
+    }
+
+    #[test]
+    fn test_decode_windows_1252_quotes() {
+        let bytes = b"/// \x93Quoted\x94 \x80 5\n";
+
+        assert_eq!(
+            decode(bytes, &InputCharset::Windows1252).unwrap(),
+            "/// \u{201C}Quoted\u{201D} \u{20AC} 5\n"
+        );
+    }
+}
@@ -1,4 +1,7 @@
 use clap::{Args, Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use crate::renderer::markdown_renderer::HeadingConfig;
 
 /// This struct represents the command-line arguments for the Rustitect.
 ///
@@ -62,7 +65,9 @@ use clap::{Args, Parser, ValueEnum};
 pub struct Cli {
     #[command(flatten)]
     pub only_flags: OnlyFlags,
-    /// Input Rust source code file. If not specified, the input will be read from stdin
+    /// Input Rust source code file. If not specified, the input will be read from stdin.
+    /// For a directory each of its Rust files is documented into an output file
+    /// named after it.
     #[arg(group = "input")]
     pub input_file: Option<String>,
 
@@ -81,14 +86,514 @@ pub struct Cli {
     #[arg(long)]
     pub preserve_names: bool,
 
+    /// The name the output files are named after when the input is read from
+    /// stdin, which allows '--preserve-names' for piped input.
+    #[arg(long, value_name = "STEM", group = "input")]
+    pub input_stdin_name: Option<String>,
+
     /// Define a prefix for the output filename. Relly useful in combination
     /// with --preserve-names flag.
     #[arg(short = 'p', long = "prefix", default_value = "")]
     pub file_name_prefix: Option<String>,
+
+    /// The pattern the names of the output files are built from, after the
+    /// prefix. The placeholders are `{stem}` for the stem of the output or input
+    /// file, `{ext}` for the extension of the format including the dot,
+    /// `{format}` for the name of the format and `{parent}` for the name of the
+    /// directory of the input file.
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        default_value = "{stem}{ext}",
+        value_parser = validate_name_pattern
+    )]
+    pub name_pattern: String,
+
+    /// Directory the output files are written to. It is created if it does not
+    /// exist. If not specified, the files are written to the current directory.
+    #[arg(long, value_name = "DIR")]
+    pub output_dir: Option<String>,
+
+    /// Make the PlantUML include of the 'asciidoc-plantuml' format relative to
+    /// the directory of the AsciiDoc file instead of the current directory.
+    #[arg(long)]
+    pub relative_includes: bool,
+
+    /// Make the PlantUML include of the 'asciidoc-plantuml' format relative to
+    /// the given directory, e.g. the directory the documentation is assembled
+    /// from, instead of the current directory.
+    #[arg(long, value_name = "DIR", conflicts_with = "relative_includes")]
+    pub base_dir: Option<String>,
+
+    /// Turn the AsciiDoc output into a standalone document with a title and a
+    /// table of contents. The Markdown output starts with a list of links to
+    /// its sections instead.
+    #[arg(long)]
+    pub toc: bool,
+
+    /// Extract a PlantUML stereotype for each type from the given attribute
+    /// name or doc comment marker, e.g. `#[stereotype(Entity)]` or
+    /// `#[doc = "stereotype: Entity"]` for the marker `stereotype`.
+    #[arg(long, value_name = "MARKER")]
+    pub stereotype_from: Option<String>,
+
+    /// Replace the class diagram by a layered overview diagram, which groups
+    /// the types by module and draws the relationships between them.
+    #[arg(long)]
+    pub layered_diagram: bool,
+
+    /// Print the JSON Schema of the model written by the 'json' output format and exit.
+    #[arg(long)]
+    pub print_schema: bool,
+
+    /// Verify the rendering instead of writing the output. The generated markdown
+    /// is parsed back and compared with the parsed input, differences are printed
+    /// and lead to a non-zero exit code.
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Additionally generate one small PlantUML diagram per method, showing the
+    /// class with just that method highlighted. The diagrams are written to
+    /// numbered '.puml' files next to the output file.
+    #[arg(long)]
+    pub per_method_diagrams: bool,
+
+    /// Document only the methods whose name matches the given regular
+    /// expression. Look-around is supported, e.g. '^(?!internal_)' documents
+    /// all methods not prefixed with 'internal_'.
+    #[arg(long, value_name = "REGEX", value_parser = validate_regex)]
+    pub methods_regex: Option<String>,
+
+    /// Title of the AsciiDoc document header. If not specified, the name of the
+    /// documented type is used.
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Author line of the AsciiDoc document header.
+    #[arg(long)]
+    pub author: Option<String>,
+
+    /// Revision date of the AsciiDoc document header.
+    #[arg(long)]
+    pub revdate: Option<String>,
+
+    /// Print a unified diff of the generated output against a previously
+    /// generated file instead of writing the output.
+    #[arg(long, value_name = "OLD_FILE")]
+    pub diff: Option<String>,
+
+    /// Treat warnings as errors: if any warning was raised while processing the
+    /// input, exit with a non-zero exit code after reporting them.
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Add the default value of each field to its documentation, taken from the
+    /// `Default` impl of the struct, a derived `Default` or `#[serde(default)]`.
+    #[arg(long)]
+    pub show_defaults: bool,
+
+    /// Process jobs from stdin instead of a single input. Each line is a JSON
+    /// object `{"input": "<rust code>", "format": "<format>"}` and each result
+    /// is written as one line of JSON to stdout.
+    #[arg(long)]
+    pub jobs_stdin: bool,
+
+    /// Document the library target of the `Cargo.toml` in the current directory,
+    /// including its module tree, instead of an input file.
+    #[arg(long, group = "input")]
+    pub lib: bool,
+
+    /// Document the binary target with the given name of the `Cargo.toml` in the
+    /// current directory, including its module tree, instead of an input file.
+    #[arg(long, value_name = "NAME", group = "input")]
+    pub bin: Option<String>,
+
+    /// Document the inner types of methods returning `Result` or `Option` in a
+    /// returns section, unless the method documentation already has one.
+    #[arg(long)]
+    pub document_returns: bool,
+
+    /// Draw a dependency arrow from each generic type to the traits bounding its
+    /// generic parameters, e.g. `Cache ..> Hash` for `Cache<K: Hash>`.
+    #[arg(long)]
+    pub bound_arrows: bool,
+
+    /// Leave traits of the standard library like `Clone` or `Hash` out of the
+    /// arrows drawn by --bound-arrows.
+    #[arg(long, requires = "bound_arrows")]
+    pub exclude_std_bounds: bool,
+
+    /// Document private items as well. By default only public fields, methods
+    /// and constants are documented, like rustdoc does.
+    #[arg(long)]
+    pub document_private_items: bool,
+
+    /// Add the source code of each method to its documentation as Rust code block.
+    #[arg(long)]
+    pub embed_source: bool,
+
+    /// Extract the code blocks of the example sections of the documentation
+    /// into runnable test files in the given directory.
+    #[arg(long, value_name = "DIR")]
+    pub extract_examples: Option<String>,
+
+    /// When to color warnings and errors printed to stderr.
+    #[arg(long, value_name = "WHEN", default_value = "auto")]
+    pub color: ColorChoice,
+
+    /// Include `#[cfg(test)]` modules, which are skipped by default as they are
+    /// not part of the documented API.
+    #[arg(long)]
+    pub include_tests: bool,
+
+    /// Language tag of the fenced PlantUML block in the Markdown output, e.g.
+    /// `puml` or `uml`, to match the renderer of the Markdown.
+    #[arg(long, value_name = "TAG", default_value = "plantuml")]
+    pub diagram_fence: String,
+
+    /// Strip trailing whitespace from every line and end every written file
+    /// with exactly one newline, to keep the generated files clean in version control.
+    #[arg(long)]
+    pub normalize_whitespace: bool,
+
+    /// Skip the generation of the PlantUML diagram, e.g. for fast text-only docs
+    /// or code the diagram generation can't handle. The output has no diagram.
+    #[arg(long, conflicts_with_all = ["plantuml_only", "layered_diagram", "per_method_diagrams"])]
+    pub no_plantuml: bool,
+
+    /// Put a 'Since <version>' marker in front of the documentation of each item
+    /// whose doc comment names the version it was introduced in. The first group
+    /// of the regular expression captures the version. By default a '# Since'
+    /// section or a '@since' tag is recognized.
+    #[arg(
+        long,
+        value_name = "REGEX",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = r"(?m)(?:^#+ Since\s+|@since\s+)(\S+)",
+        value_parser = validate_regex
+    )]
+    pub since: Option<String>,
+
+    /// Exclude structs, fields, methods and constants carrying the attribute from
+    /// the documentation, e.g. 'internal' for '#[internal]' or '#[doc(alias = "internal")]'.
+    /// Can be given multiple times.
+    #[arg(long, value_name = "ATTR")]
+    pub exclude_attr: Vec<String>,
+
+    /// The kind of the input. Markdown input is converted to the output format
+    /// directly, without parsing Rust code, e.g. to convert a Markdown document
+    /// to AsciiDoc.
+    #[arg(long, value_enum, default_value = "rust")]
+    pub input_kind: InputKind,
+
+    /// Give the method sections of the AsciiDoc output stable anchors like
+    /// 'Person_introduce' and list the methods as cross-references below the
+    /// documentation of the type.
+    #[arg(long)]
+    pub method_anchors: bool,
+
+    /// Write a PlantUML component diagram of the modules and their dependencies,
+    /// inferred from the 'use' declarations, to the given file. Meant for the
+    /// module tree of a Cargo target, see '--lib' and '--bin'.
+    #[arg(long, value_name = "PATH")]
+    pub component_diagram: Option<String>,
+
+    /// Fill the empty documentation of items with a placeholder text, so
+    /// undocumented items can be told apart from broken output.
+    #[arg(
+        long,
+        value_name = "TEXT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "_No documentation available._"
+    )]
+    pub missing_doc_text: Option<String>,
+
+    /// Additionally write the documentation of each method to its own file named
+    /// '<Class>_<method>', e.g. for a wiki with one page per symbol. An index file
+    /// '<Class>_methods' links to them. Only for the Markdown and AsciiDoc formats.
+    #[arg(long)]
+    pub split_methods: bool,
+
+    /// Link the pages of '--split-methods' relative to the index, including the
+    /// file name prefix, and with 'xref:' in AsciiDoc, which points at the rendered
+    /// pages, so the links also work when the documentation is served from a subpath.
+    #[arg(long, requires = "split_methods")]
+    pub relative_links: bool,
+
+    /// The PlantUML keyword structs are drawn with. 'auto' draws structs without
+    /// methods as 'struct' and structs with methods as 'class', which tells data
+    /// structs apart from behavior-bearing types.
+    #[arg(long, value_enum, default_value = "class")]
+    pub plantuml_struct_keyword: StructKeyword,
+
+    /// Seconds pandoc may take for a conversion to AsciiDoc, after which it is
+    /// killed and the run fails.
+    #[arg(long, value_name = "SECS", default_value_t = 30)]
+    pub pandoc_timeout: u64,
+
+    /// Retry a pandoc conversion once, if it timed out.
+    #[arg(long)]
+    pub pandoc_retry: bool,
+
+    /// Group the members of each class in the diagram by their visibility into
+    /// public, crate and private sections, and the methods in the text into the
+    /// public API and the internal ones. By default the source order is kept.
+    #[arg(long)]
+    pub group_by_visibility: bool,
+
+    /// Attach the documentation of each field as a note to its member in the
+    /// diagram. Notes longer than the given number of characters are truncated.
+    #[arg(
+        long,
+        value_name = "MAX_LENGTH",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "80"
+    )]
+    pub field_notes: Option<usize>,
+
+    /// Write an outline of the module tree of the Cargo target, with the file
+    /// each module is read from, as nested AsciiDoc list to the given file.
+    /// Requires '--lib' or '--bin'.
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "module_tree.adoc"
+    )]
+    pub module_tree: Option<String>,
+
+    /// Leave out the PlantUML diagram if the input has no types to draw, so no
+    /// empty '.puml' file and no include of it is written.
+    #[arg(long)]
+    pub emit_plantuml_only_when_nonempty: bool,
+
+    /// Abbreviate the types of the members in the diagram which are longer than
+    /// the given number of characters, like deeply nested generic types. The text
+    /// documentation keeps the full types.
+    #[arg(long, value_name = "LENGTH")]
+    pub abbreviate_types: Option<usize>,
+
+    /// Render types with their full path as written in the source. By default
+    /// the paths of the standard library types are left out, so
+    /// 'std::collections::HashMap<K, V>' is rendered as 'HashMap<K, V>'.
+    #[arg(long)]
+    pub canonical_paths: bool,
+
+    /// Read the texts and the level of the generated section headings, like
+    /// 'Constants' or 'Trait Implementations', from the given TOML file.
+    #[arg(long, value_name = "PATH", value_parser = HeadingConfig::from_toml_file)]
+    pub heading_config: Option<HeadingConfig>,
+
+    /// List all attributes of the type, like derives, 'cfg' and custom ones,
+    /// verbatim at the top of its documentation. Meant for audits.
+    #[arg(long)]
+    pub show_attributes: bool,
+
+    /// Insert the content of the given file, like '!define' or '!pragma'
+    /// directives, right after '@startuml' of each diagram. It comes before
+    /// anything the other options add, so it also works if the renderer can't
+    /// resolve '!include'.
+    #[arg(long, value_name = "PATH", value_parser = read_preamble_file)]
+    pub preamble_file: Option<String>,
+
+    /// Put the methods returning 'Self' or '&mut Self', like those of a
+    /// builder, in a 'Builder Methods' subsection below the other methods.
+    #[arg(long)]
+    pub group_builders: bool,
+
+    /// With the 'asciidoc-plantuml' format, keep diagrams of at most the given
+    /// number of lines embedded as '[plantuml]' block in the AsciiDoc, instead of
+    /// extracting them to a separate '.puml' file. Larger diagrams are still
+    /// extracted.
+    #[arg(
+        long,
+        value_name = "MAX_LINES",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "20"
+    )]
+    pub inline_diagrams: Option<usize>,
+
+    /// Pass the fenced Rust blocks, like the examples, through to the AsciiDoc
+    /// verbatim. Otherwise pandoc may reindent them or alter their escapes.
+    #[arg(long)]
+    pub verbatim_examples: bool,
+
+    /// The order of the fields in the documentation. The source order keeps the
+    /// declaration order, which matters e.g. for the memory layout.
+    #[arg(long, value_enum, default_value = "source")]
+    pub sort_fields: SortOrder,
+
+    /// The order of the methods in the documentation, independent of the fields.
+    #[arg(long, value_enum, default_value = "source")]
+    pub sort_methods: SortOrder,
+
+    /// The charset of the input file or stdin, or of all files of the '--lib' or
+    /// '--bin' target, for legacy files which are not encoded in UTF-8.
+    #[arg(long, value_enum, value_name = "ENCODING", default_value = "utf-8")]
+    pub input_charset: InputCharset,
+
+    /// Collect the '- Term: Definition' items of the 'Glossary' sections of the
+    /// documentation into an AsciiDoc glossary table written to the given file.
+    #[arg(long, value_name = "PATH")]
+    pub glossary: Option<String>,
+
+    /// When the input is a directory, continue with the other files if one of
+    /// them can't be parsed. Each failure is reported and the run exits with a
+    /// non-zero code at the end.
+    #[arg(long)]
+    pub keep_going: bool,
+
+    /// Wrap the types of the class diagram in a PlantUML namespace of the given
+    /// name, so they don't clash with the types of other diagrams combined in
+    /// one document.
+    #[arg(long, value_name = "NAME")]
+    pub plantuml_namespace: Option<String>,
+
+    /// Shorten the documentation of the type and of each member to its summary,
+    /// the first paragraph, for compact API overviews.
+    #[arg(long)]
+    pub summary_only: bool,
+
+    /// Render the fields and methods interleaved in source order in a single
+    /// 'Members' subsection, each tagged as field or method, instead of the
+    /// fields followed by the methods. The source order can't be combined with
+    /// the options sorting or grouping the members, nor with definition lists.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "sort_fields",
+            "sort_methods",
+            "group_by_visibility",
+            "group_builders",
+            "definition_lists"
+        ]
+    )]
+    pub unified_members: bool,
+
+    /// Don't print the summary of the run to stderr, which counts the files
+    /// processed, the types, fields and methods documented, the warnings and
+    /// the output files written. Warnings and errors are still printed.
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Add a legend to the class diagram explaining the visibility symbols, the
+    /// struct keyword, the stereotypes, the bound arrows and the field notes
+    /// used in it.
+    #[arg(long)]
+    pub plantuml_legend: bool,
+
+    /// When the input is a directory, write the class diagram of all types of
+    /// each file, with their relationships, to a PlantUML file named after it.
+    /// Formats writing a PlantUML file anyway are left as they are.
+    #[arg(long)]
+    pub module_diagrams: bool,
+
+    /// Order the methods of the impl blocks like the declarations of the types
+    /// they belong to, keeping the methods of each impl block together, instead
+    /// of in the order the impl blocks appear in.
+    #[arg(long)]
+    pub merge_impls_into_declaration_order: bool,
+
+    /// Highlight the lines of the assertions, like `assert_eq!`, in the Rust
+    /// blocks of the AsciiDoc output, so the documented contract stands out.
+    #[arg(long)]
+    pub highlight_asserts: bool,
+
+    /// Check the generated PlantUML with the syntax check of the `plantuml`
+    /// executable, which is taken from the environment variable 'PLANTUML_PATH'
+    /// or the path. Syntax errors are reported as warnings.
+    #[arg(long)]
+    pub validate_plantuml: bool,
+
+    /// Seconds the syntax check of '--validate-plantuml' may take, after which
+    /// `plantuml` is killed and the check is skipped with a warning.
+    #[arg(long, value_name = "SECS", default_value_t = 30)]
+    pub plantuml_timeout: u64,
+
+    /// Group the types declared in inline modules of the class diagram into a
+    /// PlantUML package named after the module path, e.g. `model::order`. Types
+    /// of the top level stay outside of any package.
+    #[arg(long)]
+    pub package_by_module: bool,
+
+    /// Clear the documentation of members which aren't `pub`, while they stay
+    /// listed with `--document-private-items`, so internal explanations aren't
+    /// published.
+    #[arg(long)]
+    pub strip_private_doc: bool,
+
+    /// Render the fields and methods in the AsciiDoc output as entries of a
+    /// definition list (`name:: documentation`) instead of sections. The entries
+    /// get no '--method-anchors', undocumented ones a placeholder definition.
+    #[arg(long)]
+    pub definition_lists: bool,
+
+    /// Add a note to each type of the class diagram with const generic
+    /// parameters, listing them with their types, e.g. `const N: usize`.
+    #[arg(long)]
+    pub const_generics_note: bool,
+
+    /// Check after writing the output that each PlantUML file included by the
+    /// AsciiDoc file exists, and fail if an include doesn't resolve.
+    #[arg(long)]
+    pub validate_includes: bool,
+
+    /// Take the output format from the extension of the output file, e.g.
+    /// Markdown for `.md`, unless `--format` is given.
+    #[arg(long)]
+    pub output_format_from_extension: bool,
+
+    /// Document the files listed one per line in the file, or in stdin for '-',
+    /// like the files of a directory, e.g. the output of `git diff --name-only`.
+    #[arg(long, value_name = "LIST", group = "input")]
+    pub files_from: Option<String>,
+
+    /// Document the impl blocks marked `#[automatically_derived]`, as generated
+    /// by a `#[derive]`, labeled as derived. By default they are left out.
+    #[arg(long)]
+    pub document_derived_impls: bool,
+}
+
+/// Reads the content of the given preamble file.
+fn read_preamble_file(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read '{path}': {e}"))
 }
 
+/// The placeholders of the `--name-pattern`.
+const NAME_PATTERN_PLACEHOLDERS: &[&str] = &["{stem}", "{ext}", "{format}", "{parent}"];
+
+/// Validates that the name pattern uses known placeholders only.
+fn validate_name_pattern(value: &str) -> Result<String, String> {
+    let placeholder = regex::Regex::new(r"\{[^{}]*\}").unwrap();
+    let unknown = placeholder
+        .find_iter(value)
+        .map(|found| found.as_str())
+        .find(|found| !NAME_PATTERN_PLACEHOLDERS.contains(found));
+    match unknown {
+        Some(unknown) => Err(format!(
+            "Unknown placeholder '{unknown}', expected one of {}",
+            NAME_PATTERN_PLACEHOLDERS.join(", ")
+        )),
+        None => Ok(value.to_string()),
+    }
+}
+
+/// Validates that the given argument is a valid regular expression.
+fn validate_regex(value: &str) -> Result<String, String> {
+    fancy_regex::Regex::new(value)
+        .map(|_| value.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// The flags skipping the AsciiDoc step. Both can be combined to generate the
+/// PlantUML and the Markdown in one run.
 #[derive(Args, Clone)]
-#[group(required = false, multiple = false)]
+#[group(required = false, multiple = true)]
 pub struct OnlyFlags {
     /// Skip the other steps and just generate the PlantUML of the code.
     #[arg(long)]
@@ -99,10 +604,71 @@ pub struct OnlyFlags {
     pub markdown_only: bool,
 }
 
-#[derive(ValueEnum, Clone, Debug, PartialEq, Eq, Hash)]
+/// The output formats. The order of the variants defines the order in which
+/// multiple outputs of a run are written.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum OutputFormat {
     Asciidoc,
     AsciidocPlantuml,
     Markdown,
     Plantuml,
+    Json,
+    Toml,
+}
+
+/// When to color the diagnostics printed to stderr.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color if stderr is a terminal.
+    Auto,
+    /// Always color, also if stderr is redirected.
+    Always,
+    /// Never color.
+    Never,
+}
+
+/// The kind of the input.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq)]
+pub enum InputKind {
+    /// Rust code, which is parsed and documented.
+    Rust,
+    /// A Markdown document, which is only converted to the output format.
+    Markdown,
+}
+
+/// The PlantUML keyword structs are drawn with.
+#[derive(ValueEnum, Clone, Debug, Default, PartialEq, Eq)]
+pub enum StructKeyword {
+    /// `class` for all structs.
+    #[default]
+    Class,
+    /// `struct` for all structs.
+    Struct,
+    /// `struct` for structs without methods, `class` for structs with methods.
+    Auto,
+}
+
+/// The charset of the input.
+#[derive(ValueEnum, Clone, Debug, Default, PartialEq, Eq)]
+pub enum InputCharset {
+    #[default]
+    #[value(name = "utf-8", alias = "utf8")]
+    Utf8,
+    /// ISO-8859-1.
+    #[value(name = "latin-1", alias = "latin1", alias = "iso-8859-1")]
+    Latin1,
+    /// The superset of Latin-1 used by legacy Windows editors.
+    #[value(name = "windows-1252", alias = "cp1252")]
+    Windows1252,
+}
+
+/// The order members are documented in.
+#[derive(ValueEnum, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// The order of declaration in the source.
+    #[default]
+    Source,
+    /// Alphabetical by name, ignoring the case and the parameter list.
+    Alphabetical,
 }
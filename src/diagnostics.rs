@@ -0,0 +1,139 @@
+//! Central collection of the diagnostics raised while processing the input.
+
+use owo_colors::OwoColorize;
+
+use crate::model::class_object::Class;
+
+/// Collects the warnings raised while parsing and processing the input, so they
+/// can be reported in one place at the end of a run instead of being printed
+/// wherever they occur.
+#[derive(Default, Debug)]
+pub struct Diagnostics {
+    warnings: Vec<String>,
+}
+
+impl Diagnostics {
+    /// Records a warning.
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.warnings.push(message.into());
+    }
+
+    /// Returns all warnings recorded so far, in the order they were raised.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Prints all recorded warnings to stderr, highlighted in yellow if `use_color` is set.
+    pub fn report(&self, use_color: bool) {
+        for warning in self.warnings() {
+            eprintln!("{}", format_warning(warning, use_color));
+        }
+    }
+}
+
+/// Counts what a run documented and wrote, printed to stderr at the end of the run
+/// unless `--quiet` is set.
+#[derive(Default, Debug, PartialEq)]
+pub struct Summary {
+    /// The number of input files processed.
+    pub files: usize,
+    /// The number of structs and traits documented.
+    pub types: usize,
+    /// The number of fields documented.
+    pub fields: usize,
+    /// The number of methods documented, including those of trait impls.
+    pub methods: usize,
+    /// The number of warnings raised.
+    pub warnings: usize,
+    /// The number of output files written, not counting the output on stdout.
+    pub output_files: usize,
+}
+
+impl Summary {
+    /// Counts the types, fields and methods of a documented class.
+    pub fn count_class(&mut self, class: &Class) {
+        self.types += usize::from(!class.name.is_empty()) + class.traits.len();
+        self.fields += class.fields.len();
+        self.methods += class.methods.len()
+            + class
+                .trait_impls
+                .iter()
+                .map(|trait_impl| trait_impl.methods.len())
+                .sum::<usize>();
+    }
+
+    /// Adds the counts of another summary, e.g. of one file of a directory.
+    pub fn add(&mut self, other: &Summary) {
+        self.files += other.files;
+        self.types += other.types;
+        self.fields += other.fields;
+        self.methods += other.methods;
+        self.warnings += other.warnings;
+        self.output_files += other.output_files;
+    }
+
+    /// Formats the summary as one line for the terminal.
+    pub fn format(&self) -> String {
+        format!(
+            "Processed {} file(s): {} type(s), {} field(s), {} method(s), {} warning(s), {} output file(s) written",
+            self.files, self.types, self.fields, self.methods, self.warnings, self.output_files
+        )
+    }
+}
+
+/// Formats a warning for the terminal, with a yellow label if `use_color` is set.
+pub fn format_warning(message: &str, use_color: bool) -> String {
+    if use_color {
+        format!("{}: {message}", "warning".yellow().bold())
+    } else {
+        format!("warning: {message}")
+    }
+}
+
+/// Formats an error for the terminal, with a red label if `use_color` is set.
+pub fn format_error(message: &str, use_color: bool) -> String {
+    if use_color {
+        format!("{}: {message}", "error".red().bold())
+    } else {
+        format!("error: {message}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_warning_and_error() {
+        assert_eq!(format_warning("skipped", false), "warning: skipped");
+        assert_eq!(format_error("failed", false), "error: failed");
+        assert_eq!(
+            format_warning("skipped", true),
+            "\u{1b}[1m\u{1b}[33mwarning\u{1b}[39m\u{1b}[0m: skipped"
+        );
+        assert!(format_error("failed", true).contains("\u{1b}[31merror"));
+    }
+
+    #[test]
+    fn test_summary_adds_the_counts_of_each_file() {
+        let mut summary = Summary::default();
+        summary.add(&Summary {
+            files: 1,
+            types: 2,
+            fields: 3,
+            methods: 4,
+            warnings: 1,
+            output_files: 2,
+        });
+        summary.add(&Summary {
+            files: 1,
+            types: 1,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            summary.format(),
+            "Processed 2 file(s): 3 type(s), 3 field(s), 4 method(s), 1 warning(s), 2 output file(s) written"
+        );
+    }
+}
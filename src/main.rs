@@ -8,13 +8,13 @@
 //! - The `cli` module defines a struct `Cli` for parsing command-line arguments.
 //! - The `plantuml_parser` module provides a function `parse_to_string` that takes a path as
 //! input and returns a PlantUML string representation of the Rust code.
-//! - The `rust_doc_parser` module provides a function `parse_code_doc_to_markdown_string` that
-//! takes a path as input and returns a Markdown string representation of the Rust code.
+//! - The `rust_doc_parser` module parses the documentation of the Rust code into a `Class`,
+//! which the renderers turn into Markdown and the other output formats.
 //! - The `asciidoc_parser` will use the extracted markdown of the `rust_doc_parser` to generate
 //! the representative asciidoc. For this pandoc is used and needs to be installed on the system.
 //!
 //! The `main` function is the entry point of the application. It initializes the logger, parses
-//! command-line arguments using `Cli::parse()`, and determines the input source (either from a
+//! command-line arguments into a `Cli`, and determines the input source (either from a
 //! file or standard input).
 //!
 //! The input is then passed to the `plantuml_parser::parse_to_string` function, which generates a
@@ -24,53 +24,544 @@
 //! This module relies on various external crates such as `clap`, `regex`, `syn` and an own
 //! version of `ruml` to function correctly.
 //!
-//! Additionally, the module also utilizes internal modules: `cli`, `model`,
-//! `parser`, and `processing` to carry out its functionalities.
+//! Additionally, the module also utilizes internal modules: `cargo_target`, `cli`, `diagnostics`,
+//! `model`, `parser`, `processing` and `renderer` to carry out its functionalities.
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io;
-use std::io::{Read, Write};
-use std::path::{Path, PathBuf};
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Component, Path, PathBuf};
 
 use clap::error::ErrorKind;
-use clap::{CommandFactory, Parser};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, ValueEnum};
+use similar::TextDiff;
 
-use processing::Processing;
+use cargo_target::{bin_entry_point, lib_entry_point, read_module_tree, render_module_outline};
+use processing::{include_plantuml, normalize_whitespace, process_job, Processing};
 
 use crate::cli::OutputFormat::AsciidocPlantuml;
-use crate::cli::{Cli, OutputFormat};
+use crate::cli::{Cli, ColorChoice, InputCharset, InputKind, OutputFormat};
+use crate::diagnostics::{format_error, Summary};
+use crate::model::class_object::json_schema;
 
+mod cargo_target;
+mod charset;
 mod cli;
+mod diagnostics;
 mod model;
 mod parser;
+mod plantuml_validator;
 mod processing;
+mod renderer;
 
 /// The main entry point of the Rustitect application.
 ///
 /// Processes the command-line arguments, and orchestrates
 /// the reading, processing, and writing of data.
 fn main() {
-    let mut args = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    // an explicit format wins over the one of the extension
+    if args.output_format_from_extension
+        && matches.value_source("format") == Some(ValueSource::DefaultValue)
+    {
+        if let Some(format) = args.output_file.as_deref().and_then(format_from_extension) {
+            args.format = format;
+        }
+    }
+
+    if args.print_schema {
+        println!("{}", json_schema());
+        return;
+    }
+
+    validate_input_kind(&args);
+    if args.split_methods
+        && matches!(
+            args.format,
+            OutputFormat::Plantuml | OutputFormat::Json | OutputFormat::Toml
+        )
+    {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--split-methods requires the Markdown or an AsciiDoc format",
+            )
+            .exit();
+    }
+
+    if args.module_tree.is_some() && !args.lib && args.bin.is_none() {
+        Cli::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "--module-tree requires --lib or --bin",
+            )
+            .exit();
+    }
+
+    if args.jobs_stdin {
+        run_jobs_from_stdin(&args);
+        return;
+    }
+
+    if let Some(files_from) = &args.files_from {
+        process_files_from(&args, files_from);
+        return;
+    }
+
+    if let Some(input_dir) = args
+        .input_file
+        .as_deref()
+        .filter(|path| Path::new(path).is_dir())
+    {
+        process_directory(&args, input_dir);
+        return;
+    }
+    if args.module_diagrams {
+        Cli::command()
+            .error(
+                ErrorKind::InvalidValue,
+                "--module-diagrams requires a directory as input",
+            )
+            .exit();
+    }
+
+    let cargo_target_entry_point = cargo_target_entry_point(&args);
+    if let Some(entry_point) = &cargo_target_entry_point {
+        args.input_file = Some(entry_point.to_string_lossy().to_string());
+    }
 
     handle_preserve_names_and_set_output_file(&mut args);
 
-    let input = read_input(&args.input_file);
-    let processing = Processing { args: args.clone() };
-    let output = processing.start(&input);
+    let input = match &cargo_target_entry_point {
+        Some(entry_point) => read_module_tree(entry_point, args.include_tests, &args.input_charset)
+            .unwrap_or_else(|e| {
+                Cli::command().error(ErrorKind::Io, e).exit();
+            }),
+        None => read_input(&args.input_file, &args.input_charset),
+    };
+    let mut processing = Processing::new(args.clone());
+
+    if args.verify {
+        verify_input(&mut processing, &input);
+        return;
+    }
+
+    let mut output = processing.start(&input);
+
+    if let Some(old_file) = &args.diff {
+        print_diff(output, &args, old_file);
+        report_diagnostics(&processing);
+        return;
+    }
+
+    let output_stem = output_stem(&args);
+    let include_target = if args.output_file.is_some() {
+        let plantuml_file_name = prefixed_file_name(
+            &args,
+            &output_file_name(&args, &output_stem, &AsciidocPlantuml),
+        );
+        plantuml_include_target(&args, &plantuml_file_name)
+    } else {
+        String::from(STDOUT_INCLUDE_TARGET)
+    };
+    include_plantuml_file(&mut output, &include_target);
+    let mut output_files = write_output(output, &args, &output_stem);
+    if args.validate_includes {
+        validate_includes(&args, &output_stem);
+    }
+
+    if args.per_method_diagrams {
+        let diagrams = processing.per_method_diagrams(&input);
+        if args.output_file.is_some() {
+            output_files += diagrams.len();
+        }
+        write_per_method_diagrams(diagrams, &args, &output_stem);
+    }
+
+    if args.split_methods {
+        let pages = processing.split_methods(&input);
+        output_files += pages.len();
+        write_split_methods(pages, &args);
+    }
+
+    if let Some(diagram_path) = &args.component_diagram {
+        let diagram = processing.component_diagram(&input);
+        std::fs::write(diagram_path, diagram).expect("Failed to write component diagram");
+        output_files += 1;
+    }
+
+    if let (Some(outline_path), Some(entry_point)) = (&args.module_tree, &cargo_target_entry_point)
+    {
+        let outline = render_module_outline(entry_point, args.include_tests, &args.input_charset)
+            .unwrap_or_else(|e| {
+                Cli::command().error(ErrorKind::Io, e).exit();
+            });
+        std::fs::write(outline_path, outline).expect("Failed to write module tree");
+        output_files += 1;
+    }
+
+    if let Some(examples_dir) = &args.extract_examples {
+        let examples = processing.extract_examples(&input);
+        output_files += examples.len();
+        write_examples(examples, examples_dir);
+    }
+
+    if let Some(glossary_path) = &args.glossary {
+        let glossary = processing.glossary(&input);
+        std::fs::write(glossary_path, glossary).expect("Failed to write glossary");
+        output_files += 1;
+    }
+
+    let summary = file_summary(&processing, output_files);
+    report_diagnostics(&processing);
+    report_summary(&summary, &args);
+}
+
+/// Documents each Rust file of the directory, or each Markdown file with
+/// `--input-kind=markdown`, into output files named after it, like with
+/// `--preserve-names`.
+///
+/// A file which can't be parsed aborts the run, unless `--keep-going` is set. Then
+/// the failure is reported and the other files are still documented, but the run
+/// exits with a non-zero code at the end.
+fn process_directory(args: &Cli, input_dir: &str) {
+    let extension = input_file_extension(args);
+    let mut input_files: Vec<PathBuf> = std::fs::read_dir(input_dir)
+        .unwrap_or_else(|e| Cli::command().error(ErrorKind::Io, e).exit())
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == extension))
+        .collect();
+    input_files.sort();
+
+    process_files(args, input_files, input_dir);
+}
+
+/// Documents each file of the newline separated list, read from the file or from
+/// stdin for `-`, like the files of a directory. Like this only the files changed
+/// in a commit can be documented, e.g. from `git diff --name-only`.
+///
+/// Files with another extension than the input kind and files which don't exist,
+/// like deleted ones, are skipped.
+fn process_files_from(args: &Cli, files_from: &str) {
+    let list = if files_from == "-" {
+        let mut list = String::new();
+        io::stdin()
+            .read_to_string(&mut list)
+            .unwrap_or_else(|e| Cli::command().error(ErrorKind::Io, e).exit());
+        list
+    } else {
+        std::fs::read_to_string(files_from)
+            .unwrap_or_else(|e| Cli::command().error(ErrorKind::Io, e).exit())
+    };
+    let extension = input_file_extension(args);
+    let input_files: Vec<PathBuf> = list
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == extension))
+        .collect();
+
+    process_files(args, input_files, files_from);
+}
+
+/// The extension of the input files of the input kind, without the dot.
+fn input_file_extension(args: &Cli) -> &'static str {
+    match args.input_kind {
+        InputKind::Rust => "rs",
+        InputKind::Markdown => "md",
+    }
+}
+
+/// Documents each of the files into output files named after it, see
+/// [process_directory]. The source of the files names them in the error message.
+fn process_files(args: &Cli, input_files: Vec<PathBuf>, source: &str) {
+    validate_multiple_file_input(args);
+    reject_colliding_output_files(args, &input_files);
+    let mut failures = 0;
+    let mut strict_failures = 0;
+    let mut summary = Summary::default();
+    for input_file in input_files {
+        let input_file = input_file.to_string_lossy().to_string();
+        let input = read_input(&Some(input_file.clone()), &args.input_charset);
+        if args.input_kind == InputKind::Rust {
+            if let Err(e) = syn::parse_file(&input) {
+                let message = format!("Failed to parse '{input_file}': {e}");
+                if !args.keep_going {
+                    Cli::command()
+                        .error(ErrorKind::InvalidValue, message)
+                        .exit();
+                }
+                eprintln!("{}", format_error(&message, use_color(args)));
+                failures += 1;
+                continue;
+            }
+        }
+
+        let file_args = file_output_args(args, input_file);
+        let mut processing = Processing::new(file_args.clone());
+        let mut output = processing.start(&input);
+        let output_stem = output_stem(&file_args);
+        let plantuml_file_name = prefixed_file_name(
+            &file_args,
+            &output_file_name(&file_args, &output_stem, &AsciidocPlantuml),
+        );
+        include_plantuml_file(
+            &mut output,
+            &plantuml_include_target(&file_args, &plantuml_file_name),
+        );
+        let mut output_files = write_output(output, &file_args, &output_stem);
+        if args.validate_includes {
+            validate_includes(&file_args, &output_stem);
+        }
+        if args.module_diagrams {
+            output_files += write_module_diagram(&processing, &input, &file_args, &output_stem);
+        }
+        summary.add(&file_summary(&processing, output_files));
+        if report_file_diagnostics(&processing) {
+            if !args.keep_going {
+                std::process::exit(1);
+            }
+            strict_failures += 1;
+        }
+    }
+
+    report_summary(&summary, args);
+    if failures > 0 {
+        let message = format!("{failures} file(s) of '{source}' failed to parse");
+        eprintln!("{}", format_error(&message, use_color(args)));
+    }
+    if strict_failures > 0 {
+        let message =
+            format!("{strict_failures} file(s) of '{source}' raised warnings in strict mode");
+        eprintln!("{}", format_error(&message, use_color(args)));
+    }
+    if failures > 0 || strict_failures > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// The arguments to document one of several input files with, which name its
+/// output files after it.
+fn file_output_args(args: &Cli, input_file: String) -> Cli {
+    let mut file_args = args.clone();
+    file_args.input_file = Some(input_file);
+    file_args.preserve_names = true;
+    handle_preserve_names_and_set_output_file(&mut file_args);
+    file_args
+}
+
+/// Exits with an error if two of the input files would be documented into the
+/// same output file, like `src/a/mod.rs` and `src/b/mod.rs` of a list, unless the
+/// `--name-pattern` tells them apart, e.g. by `{parent}`.
+fn reject_colliding_output_files(args: &Cli, input_files: &[PathBuf]) {
+    let mut output_files: BTreeMap<String, &PathBuf> = BTreeMap::new();
+    for input_file in input_files {
+        let file_args = file_output_args(args, input_file.to_string_lossy().to_string());
+        let file_name = output_file_name(&file_args, &output_stem(&file_args), &args.format);
+        if let Some(other_input_file) = output_files.insert(file_name.clone(), input_file) {
+            Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "'{}' and '{}' would both be documented into '{file_name}', \
+                        use a --name-pattern with '{{parent}}' to tell them apart",
+                        other_input_file.display(),
+                        input_file.display()
+                    ),
+                )
+                .exit();
+        }
+    }
+}
+
+/// Writes the class diagram of all types of a file of the directory to a PlantUML
+/// file named after it, unless the output format writes one already.
+///
+/// # Returns
+///
+/// The number of files written, `0` if the file defines no types.
+fn write_module_diagram(
+    processing: &Processing,
+    input: &String,
+    file_args: &Cli,
+    output_stem: &str,
+) -> usize {
+    if matches!(file_args.format, OutputFormat::Plantuml | AsciidocPlantuml) {
+        return 0;
+    }
+    let Some(diagram) = processing.module_diagram(input) else {
+        return 0;
+    };
+    let file_name = output_file_name(file_args, output_stem, &OutputFormat::Plantuml);
+    let output_file_path = get_output_file_path(file_args, &file_name);
+    std::fs::write(output_file_path, diagram).expect("Failed to write module diagram");
+    1
+}
+
+/// Reports the diagnostics raised while processing the input.
+///
+/// In strict mode any warning is treated as error, which leads to a non-zero exit code.
+fn report_diagnostics(processing: &Processing) {
+    if report_file_diagnostics(processing) {
+        std::process::exit(1);
+    }
+}
+
+/// Reports the diagnostics raised while processing one of several input files,
+/// without exiting.
+///
+/// # Returns
+///
+/// `true` if the file failed, because of warnings in strict mode.
+fn report_file_diagnostics(processing: &Processing) -> bool {
+    let use_color = use_color(&processing.args);
+    processing.diagnostics.report(use_color);
+    let failed = processing.args.strict && !processing.diagnostics.warnings().is_empty();
+    if failed {
+        let message = format!(
+            "{} warning(s) raised in strict mode",
+            processing.diagnostics.warnings().len()
+        );
+        eprintln!("{}", format_error(&message, use_color));
+    }
+    failed
+}
+
+/// Completes the summary of the processing of one input file with its warnings
+/// and the number of output files written.
+fn file_summary(processing: &Processing, output_files: usize) -> Summary {
+    Summary {
+        files: 1,
+        warnings: processing.diagnostics.warnings().len(),
+        output_files,
+        ..processing.summary
+    }
+}
+
+/// Prints the summary of the run to stderr, unless `--quiet` is set.
+fn report_summary(summary: &Summary, args: &Cli) {
+    if !args.quiet {
+        eprintln!("{}", summary.format());
+    }
+}
+
+/// Determines whether the diagnostics on stderr are colored. With `auto` they
+/// are colored if stderr is a terminal, so piped output stays plain.
+fn use_color(args: &Cli) -> bool {
+    match args.color {
+        ColorChoice::Auto => io::stderr().is_terminal(),
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    }
+}
+
+/// Processes the jobs read line by line from stdin until it is closed.
+///
+/// Each line is a JSON job and for each the result is written as one line of JSON
+/// to stdout, so a caller can keep the process running for many conversions.
+fn run_jobs_from_stdin(args: &Cli) {
+    let mut stdout = io::stdout();
+    for line in io::stdin().lines() {
+        let line = line.expect("Failed to read from stdin");
+        if line.trim().is_empty() {
+            continue;
+        }
+        let result = process_job(&line, args);
+        let result_line = serde_json::to_string(&result).expect("Failed to serialize to JSON");
+        writeln!(stdout, "{result_line}").expect("Failed to write to stdout");
+        stdout.flush().expect("Failed to write to stdout");
+    }
+}
+
+/// Verifies the rendering of the input and prints the result to stderr.
+///
+/// Exits with a non-zero exit code if the verification found any differences.
+fn verify_input(processing: &mut Processing, input: &String) {
+    let differences = processing.verify(input);
+    report_diagnostics(processing);
+    if differences.is_empty() {
+        eprintln!("Verification passed");
+    } else {
+        eprintln!(
+            "{}",
+            format_error("Verification failed:", use_color(&processing.args))
+        );
+        for difference in differences {
+            eprintln!("  {difference}");
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Prints a unified diff of a previously generated file against the current output.
+///
+/// The output of the format actually generated is compared, e.g. the Markdown for
+/// `--markdown-only`. For the 'asciidoc-plantuml' format the AsciiDoc part is
+/// compared, with the include resolved as if the output had been written to the
+/// previous file.
+fn print_diff(mut output: BTreeMap<OutputFormat, String>, args: &Cli, old_file: &str) {
+    let old_content =
+        read_input(&Some(old_file.to_string()), &InputCharset::Utf8).replace("\r\n", "\n");
+    let new_content = if output.contains_key(&AsciidocPlantuml) {
+        let old_file_stem = Path::new(old_file).file_stem().unwrap().to_str().unwrap();
+        let include_target = plantuml_include_target(args, &format!("{old_file_stem}.puml"));
+        include_plantuml_file(&mut output, &include_target);
+        output.remove(&OutputFormat::Asciidoc).unwrap_or_default()
+    } else {
+        // the only flags and a diagram staying inline give another format than `--format`
+        output
+            .remove(&args.format)
+            .or_else(|| output.into_values().next())
+            .unwrap_or_default()
+    };
+
+    let diff = TextDiff::from_lines(&old_content, &new_content);
+    let unified_diff = diff
+        .unified_diff()
+        .header(old_file, "generated")
+        .to_string();
+    io::stdout()
+        .write_all(unified_diff.as_bytes())
+        .expect("Failed to write to stdout");
+}
 
-    let prefix = args.file_name_prefix.expect("File name prefix not set");
-    write_output(output, &args.output_file, prefix);
+/// Determines the entry point of the Cargo target selected by `--lib` or `--bin`
+/// from the `Cargo.toml` in the current directory.
+///
+/// Returns `None` if no Cargo target is selected.
+fn cargo_target_entry_point(args: &Cli) -> Option<PathBuf> {
+    let manifest_path = Path::new("Cargo.toml");
+    let entry_point = if args.lib {
+        lib_entry_point(manifest_path)
+    } else if let Some(name) = &args.bin {
+        bin_entry_point(manifest_path, name)
+    } else {
+        return None;
+    };
+    match entry_point {
+        Ok(entry_point) => Some(entry_point),
+        Err(e) => Cli::command().error(ErrorKind::InvalidValue, e).exit(),
+    }
 }
 
 /// Checks if the 'preserve_names' argument is provided.
 ///
 /// If so, ensures that the input isn't coming from stdin, as name preservation
-/// from stdin isn't supported. It also constructs the output file name based on
-/// the input file name and the desired output format.
+/// from stdin isn't supported unless a name is given by `input_stdin_name`. It
+/// also constructs the output file name based on the input file name and the
+/// desired output format.
 fn handle_preserve_names_and_set_output_file(args: &mut Cli) {
     let stdin = PathBuf::from("-");
     if args.preserve_names {
+        if let (None, Some(stdin_name)) = (&args.input_file, &args.input_stdin_name) {
+            let extension = get_output_format_extension(&args.format);
+            args.output_file = Some(format!("{stdin_name}{extension}"));
+            return;
+        }
         let input_path = PathBuf::from(args.input_file.as_ref().unwrap());
 
         if input_path == stdin {
@@ -88,6 +579,70 @@ fn handle_preserve_names_and_set_output_file(args: &mut Cli) {
     }
 }
 
+/// Writes the page of each method and their index next to the output files.
+fn write_split_methods(pages: Vec<(String, String)>, args: &Cli) {
+    for (file_name, content) in pages {
+        let output_file_path = get_output_file_path(args, &file_name);
+        let mut file = File::create(output_file_path).expect("Failed to create output file");
+        file.write_all(content.as_bytes())
+            .expect("Failed to write output file");
+    }
+}
+
+/// Exits with an error if options documenting a single input are combined with
+/// several input files, from a directory or from `--files-from`.
+fn validate_multiple_file_input(args: &Cli) {
+    let single_input_options = [
+        (args.verify, "--verify"),
+        (args.diff.is_some(), "--diff"),
+        (args.per_method_diagrams, "--per-method-diagrams"),
+        (args.split_methods, "--split-methods"),
+        (args.extract_examples.is_some(), "--extract-examples"),
+        (args.component_diagram.is_some(), "--component-diagram"),
+        (args.module_tree.is_some(), "--module-tree"),
+        (args.glossary.is_some(), "--glossary"),
+    ];
+    if let Some((_, option)) = single_input_options.iter().find(|(is_set, _)| *is_set) {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!("{option} requires a single input file, not a directory or --files-from"),
+            )
+            .exit();
+    }
+}
+
+/// Exits with an error if options requiring Rust input are combined with Markdown input.
+fn validate_input_kind(args: &Cli) {
+    if args.input_kind == InputKind::Rust {
+        return;
+    }
+    let rust_only_options = [
+        (args.jobs_stdin, "--jobs-stdin"),
+        (args.only_flags.plantuml_only, "--plantuml-only"),
+        (args.only_flags.markdown_only, "--markdown-only"),
+        (args.format == OutputFormat::Plantuml, "--format=plantuml"),
+        (args.format == OutputFormat::Json, "--format=json"),
+        (args.format == OutputFormat::Toml, "--format=toml"),
+        (args.verify, "--verify"),
+        (args.per_method_diagrams, "--per-method-diagrams"),
+        (args.extract_examples.is_some(), "--extract-examples"),
+        (args.component_diagram.is_some(), "--component-diagram"),
+        (args.module_tree.is_some(), "--module-tree"),
+        (args.glossary.is_some(), "--glossary"),
+        (args.module_diagrams, "--module-diagrams"),
+        (args.split_methods, "--split-methods"),
+    ];
+    if let Some((_, option)) = rust_only_options.iter().find(|(is_set, _)| *is_set) {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!("{option} requires Rust input, not --input-kind=markdown"),
+            )
+            .exit();
+    }
+}
+
 /// Determines the appropriate file extension based on the specified output format.
 fn get_output_format_extension(format: &OutputFormat) -> &str {
     match format {
@@ -95,55 +650,128 @@ fn get_output_format_extension(format: &OutputFormat) -> &str {
         OutputFormat::AsciidocPlantuml => ".puml",
         OutputFormat::Markdown => ".md",
         OutputFormat::Plantuml => ".puml",
+        OutputFormat::Json => ".json",
+        OutputFormat::Toml => ".toml",
+    }
+}
+
+/// Determines the output format from the extension of the output file, `None` for
+/// an unknown extension.
+fn format_from_extension(output_file: &str) -> Option<OutputFormat> {
+    let extension = Path::new(output_file).extension()?.to_str()?;
+    match extension.to_lowercase().as_str() {
+        "adoc" | "asciidoc" => Some(OutputFormat::Asciidoc),
+        "md" | "markdown" => Some(OutputFormat::Markdown),
+        "puml" | "plantuml" => Some(OutputFormat::Plantuml),
+        "json" => Some(OutputFormat::Json),
+        "toml" => Some(OutputFormat::Toml),
+        _ => None,
     }
 }
 
 /// Reads the content of the specified file or from stdin if no file is provided.
-fn read_input(input_file: &Option<String>) -> String {
-    let mut input_buffer = String::new();
+fn read_input(input_file: &Option<String>, charset: &InputCharset) -> String {
+    let mut input_buffer = Vec::new();
 
     match input_file {
         Some(input_file) => {
             let input_path = PathBuf::from(input_file);
             let mut file = File::open(input_path).expect("Failed to open input file");
-            file.read_to_string(&mut input_buffer)
+            file.read_to_end(&mut input_buffer)
                 .expect("Failed to read input file");
         }
         None => {
             io::stdin()
-                .read_to_string(&mut input_buffer)
+                .read_to_end(&mut input_buffer)
                 .expect("Failed to read from stdin");
         }
     };
 
-    input_buffer
+    charset::decode(&input_buffer, charset).unwrap_or_else(|e| {
+        Cli::command().error(ErrorKind::InvalidValue, e).exit();
+    })
+}
+
+/// Determines the file stem the output files are named after.
+///
+/// This is the stem of the output file or, when writing to stdout, the stem of
+/// the input file. Input from stdin results in the name given for it, or 'stdin'.
+fn output_stem(args: &Cli) -> String {
+    args.output_file
+        .as_ref()
+        .or(args.input_file.as_ref())
+        .or(args.input_stdin_name.as_ref())
+        .and_then(|file| Path::new(file).file_stem())
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| String::from("stdin"))
+}
+
+/// The include target of the PlantUML diagram in the AsciiDoc output written to
+/// stdout. No PlantUML file is written then, so the file name is left to be filled in.
+const STDOUT_INCLUDE_TARGET: &str = "FILENAME.puml";
+
+/// Replaces the PlantUML diagram embedded in the AsciiDoc output by an include of
+/// the separate PlantUML file, if the output is combined of both.
+///
+/// The include target is the path of the written PlantUML file, see
+/// [plantuml_include_target], or [STDOUT_INCLUDE_TARGET] for stdout.
+fn include_plantuml_file(output: &mut BTreeMap<OutputFormat, String>, include_target: &str) {
+    if !output.contains_key(&AsciidocPlantuml) {
+        return;
+    }
+    if let Some(asciidoc) = output.get_mut(&OutputFormat::Asciidoc) {
+        *asciidoc = include_plantuml(asciidoc, include_target);
+    }
+}
+
+/// Builds the name of the output file of a format from the `--name-pattern`.
+fn output_file_name(args: &Cli, output_stem: &str, format: &OutputFormat) -> String {
+    let parent = args
+        .input_file
+        .as_ref()
+        .and_then(|file| Path::new(file).parent())
+        .and_then(|parent| parent.file_name())
+        .map(|parent| parent.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let format_name = format
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default();
+    args.name_pattern
+        .replace("{stem}", output_stem)
+        .replace("{ext}", get_output_format_extension(format))
+        .replace("{format}", &format_name)
+        .replace("{parent}", &parent)
 }
 
-/// Writes the processed output either to the specified file or to stdout.
+/// Writes the processed output either to files named after the output stem or to stdout.
+///
+/// # Returns
+///
+/// The number of files written, `0` when writing to stdout.
 fn write_output(
-    output: HashMap<OutputFormat, String>,
-    output_file: &Option<String>,
-    file_name_prefix: String,
-) {
-    match output_file {
-        Some(output_file) => {
-            let file_name = Path::new(output_file)
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap();
-            let output_is_combined = output.contains_key(&AsciidocPlantuml);
-            for (format, mut content) in output {
-                if output_is_combined && format == OutputFormat::Asciidoc {
-                    content = content.replace("FILENAME", file_name);
-                }
-                let extension = get_output_format_extension(&format);
-                let output_file_name = format!("{}{}{}", file_name_prefix, file_name, extension);
+    mut output: BTreeMap<OutputFormat, String>,
+    args: &Cli,
+    output_stem: &str,
+) -> usize {
+    if args.normalize_whitespace {
+        for content in output.values_mut() {
+            *content = normalize_whitespace(content);
+        }
+    }
+    match &args.output_file {
+        Some(_) => {
+            reject_colliding_file_names(&output, args, output_stem);
+            let file_count = output.len();
+            for (format, content) in output {
+                let output_file_path =
+                    get_output_file_path(args, &output_file_name(args, output_stem, &format));
                 let mut file =
-                    File::create(output_file_name).expect("Failed to create output file");
+                    File::create(output_file_path).expect("Failed to create output file");
                 file.write_all(content.as_bytes())
                     .expect("Failed to write output file");
             }
+            file_count
         }
         None => {
             let output_content = output
@@ -154,6 +782,194 @@ fn write_output(
             io::stdout()
                 .write_all(output_content.as_bytes())
                 .expect("Failed to write to stdout");
+            0
+        }
+    }
+}
+
+/// Exits with an error if the `--name-pattern` gives several formats of the output
+/// the same file name, as they would overwrite each other.
+fn reject_colliding_file_names(
+    output: &BTreeMap<OutputFormat, String>,
+    args: &Cli,
+    output_stem: &str,
+) {
+    let mut file_names = BTreeMap::new();
+    for format in output.keys() {
+        let file_name = output_file_name(args, output_stem, format);
+        if let Some(other_format) = file_names.insert(file_name.clone(), format) {
+            let format_names = [other_format, format].map(|format| {
+                format
+                    .to_possible_value()
+                    .map(|value| value.get_name().to_string())
+                    .unwrap_or_default()
+            });
+            Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "The name pattern '{}' names both the '{}' and the '{}' output \
+                        '{file_name}', use '{{ext}}' or '{{format}}' to tell them apart",
+                        args.name_pattern, format_names[0], format_names[1]
+                    ),
+                )
+                .exit();
+        }
+    }
+}
+
+/// Constructs the path of an output file from the file name, the file name
+/// prefix and the output directory. The output directory is created if needed.
+fn get_output_file_path(args: &Cli, file_name: &str) -> PathBuf {
+    match &args.output_dir {
+        Some(output_dir) => {
+            std::fs::create_dir_all(output_dir).expect("Failed to create output directory");
+            Path::new(output_dir).join(prefixed_file_name(args, file_name))
+        }
+        None => PathBuf::from(prefixed_file_name(args, file_name)),
+    }
+}
+
+/// Prepends the file name prefix to the name of an output file.
+fn prefixed_file_name(args: &Cli, file_name: &str) -> String {
+    let prefix = args
+        .file_name_prefix
+        .as_deref()
+        .expect("File name prefix not set");
+    format!("{prefix}{file_name}")
+}
+
+/// Determines the target of the PlantUML include in the AsciiDoc output.
+///
+/// By default the target is the path of the PlantUML file as seen from the current
+/// working directory. With `relative_includes` it is relative to the directory of
+/// the AsciiDoc file instead, so the include resolves regardless of where the
+/// documentation build is invoked. With `base_dir` it is relative to the given
+/// directory, for documents assembled from there.
+fn plantuml_include_target(args: &Cli, file_name: &str) -> String {
+    let output_dir = PathBuf::from(args.output_dir.as_deref().unwrap_or_default());
+    let plantuml_path = output_dir.join(file_name);
+    let include_path = if let Some(base_dir) = &args.base_dir {
+        let base_dir = Path::new(base_dir);
+        if base_dir.is_absolute() == plantuml_path.is_absolute() {
+            relative_path(&plantuml_path, base_dir)
+        } else {
+            let current_dir = std::env::current_dir().expect("Failed to get current directory");
+            relative_path(
+                &current_dir.join(&plantuml_path),
+                &current_dir.join(base_dir),
+            )
+        }
+    } else if args.relative_includes {
+        relative_path(&plantuml_path, &output_dir)
+    } else {
+        plantuml_path
+    };
+    include_path.to_string_lossy().replace('\\', "/")
+}
+
+/// Checks that each PlantUML file included by the written AsciiDoc file exists, and
+/// exits with an error for an include which doesn't resolve. The include targets are
+/// resolved the way they were generated, see [plantuml_include_target].
+///
+/// Nothing is checked when writing to stdout or without an AsciiDoc file.
+fn validate_includes(args: &Cli, output_stem: &str) {
+    if args.output_file.is_none() {
+        return;
+    }
+    let asciidoc_path = get_output_file_path(
+        args,
+        &output_file_name(args, output_stem, &OutputFormat::Asciidoc),
+    );
+    let Ok(asciidoc) = std::fs::read_to_string(&asciidoc_path) else {
+        return;
+    };
+    let include_dir = if let Some(base_dir) = &args.base_dir {
+        PathBuf::from(base_dir)
+    } else if args.relative_includes {
+        asciidoc_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    } else {
+        PathBuf::new()
+    };
+    let include = regex::Regex::new(r"plantuml::([^\[\s]+)\[").unwrap();
+    let dangling: Vec<&str> = include
+        .captures_iter(&asciidoc)
+        .map(|captures| captures.get(1).unwrap().as_str())
+        .filter(|target| !include_dir.join(target).is_file())
+        .collect();
+    if !dangling.is_empty() {
+        Cli::command()
+            .error(
+                ErrorKind::Io,
+                format!(
+                    "The includes of '{}' don't resolve to a written PlantUML file: {}",
+                    asciidoc_path.display(),
+                    dangling.join(", ")
+                ),
+            )
+            .exit();
+    }
+}
+
+/// Computes the path of `target` relative to the directory `base`. Both paths have
+/// to be either absolute or relative to the same directory.
+fn relative_path(target: &Path, base: &Path) -> PathBuf {
+    let target_components: Vec<Component> = target.components().collect();
+    let base_components: Vec<Component> = base.components().collect();
+    let common_length = target_components
+        .iter()
+        .zip(&base_components)
+        .take_while(|(target_component, base_component)| target_component == base_component)
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in common_length..base_components.len() {
+        relative.push("..");
+    }
+    for component in &target_components[common_length..] {
+        relative.push(component);
+    }
+    relative
+}
+
+/// Writes each extracted example to its own file in the given directory, which is
+/// created if needed.
+fn write_examples(examples: Vec<(String, String)>, examples_dir: &str) {
+    std::fs::create_dir_all(examples_dir).expect("Failed to create examples directory");
+    for (file_name, content) in examples {
+        let mut file = File::create(Path::new(examples_dir).join(file_name))
+            .expect("Failed to create example file");
+        file.write_all(content.as_bytes())
+            .expect("Failed to write example file");
+    }
+}
+
+/// Writes each per-method diagram either to its own numbered PlantUML file or to stdout.
+/// The files are named like the PlantUML output by the `--name-pattern`, followed by
+/// the number of the method.
+fn write_per_method_diagrams(diagrams: Vec<String>, args: &Cli, output_stem: &str) {
+    match &args.output_file {
+        Some(_) => {
+            let file_name = output_file_name(args, output_stem, &OutputFormat::Plantuml);
+            let file_stem = file_name.strip_suffix(".puml").unwrap_or(&file_name);
+            for (index, diagram) in diagrams.iter().enumerate() {
+                let output_file_path =
+                    get_output_file_path(args, &format!("{}_{}.puml", file_stem, index + 1));
+                let mut file =
+                    File::create(output_file_path).expect("Failed to create output file");
+                file.write_all(diagram.as_bytes())
+                    .expect("Failed to write output file");
+            }
+        }
+        None => {
+            for diagram in diagrams {
+                io::stdout()
+                    .write_all(format!("\n{diagram}").as_bytes())
+                    .expect("Failed to write to stdout");
+            }
         }
     };
 }
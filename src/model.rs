@@ -1 +1,2 @@
 pub mod class_object;
+pub mod job;
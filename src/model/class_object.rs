@@ -1,4 +1,11 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
 /// Represents a class in the code, including its associated methods and documentation.
+///
+/// All members are kept in vectors in the order of the source, so every output
+/// format renders them in a stable order.
+#[derive(Serialize, JsonSchema)]
 pub struct Class {
     /// The PlantUML diagram for the class.
     pub plantuml: String,
@@ -10,13 +17,159 @@ pub struct Class {
     pub fields: Vec<Method>,
     /// The methods associated with the class.
     pub methods: Vec<Method>,
+    /// The constants and statics defined at module level.
+    pub constants: Vec<Method>,
+    /// The implementations of traits for the class.
+    pub trait_impls: Vec<TraitImpl>,
+    /// The traits defined at module level.
+    pub traits: Vec<Trait>,
+    /// The `pub use` re-exports at module level, named like `path::Name as Alias`.
+    pub reexports: Vec<Method>,
+    /// The `extern` blocks declaring foreign functions and statics.
+    pub foreign_mods: Vec<ForeignMod>,
+}
+
+impl Class {
+    /// Calls the function with the documentation of the class and of each of its members.
+    pub fn for_each_documentation(&mut self, mut function: impl FnMut(&mut String)) {
+        function(&mut self.documentation);
+        let trait_members = self.trait_impls.iter_mut().flat_map(|trait_impl| {
+            trait_impl
+                .constants
+                .iter_mut()
+                .chain(&mut trait_impl.methods)
+        });
+        let trait_methods = self
+            .traits
+            .iter_mut()
+            .flat_map(|defined_trait| defined_trait.methods.iter_mut());
+        let foreign_items = self
+            .foreign_mods
+            .iter_mut()
+            .flat_map(|foreign_mod| foreign_mod.items.iter_mut());
+        for member in self
+            .fields
+            .iter_mut()
+            .chain(&mut self.methods)
+            .chain(&mut self.constants)
+            .chain(trait_members)
+            .chain(trait_methods)
+            .chain(foreign_items)
+        {
+            function(&mut member.documentation);
+        }
+    }
 }
 
 /// Represents a method within a class, including its name and documentation.
-#[derive(Debug, PartialEq)]
+/// Fields and constants are represented the same way.
+#[derive(Debug, Default, PartialEq, Serialize, JsonSchema)]
 pub struct Method {
     /// The name of the method.
     pub name: String,
     /// The documentation for the method.
     pub documentation: String,
+    /// The line of the name in the source, if the member was parsed from Rust code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// The visibility as written in the source, e.g. `pub` or `pub(crate)`, and
+    /// `private` without visibility. `None` if unknown or inherited from a trait.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    /// The inner type `T` of a field of type `Option<T>`, which marks the field
+    /// as optional. `None` for all other members.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optional_type: Option<String>,
+    /// Whether the method returns `Self` or `&mut Self`, like the methods of a builder.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub builder: bool,
+    /// Whether the method of a trait has a default implementation, so implementors
+    /// may leave it out. The trait methods without one are required.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub provided: bool,
+}
+
+/// Represents the implementation of a trait, including its associated constants
+/// and methods.
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+pub struct TraitImpl {
+    /// The path of the implemented trait, e.g. `fmt::Display`.
+    pub name: String,
+    /// The documentation of the impl block.
+    pub documentation: String,
+    /// The associated constants defined in the impl block.
+    pub constants: Vec<Method>,
+    /// The methods defined in the impl block.
+    pub methods: Vec<Method>,
+}
+
+/// Represents a trait definition with the methods it declares.
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+pub struct Trait {
+    /// The name of the trait.
+    pub name: String,
+    /// The documentation of the trait.
+    pub documentation: String,
+    /// Whether the trait can be used as trait object `dyn Trait`. This is a
+    /// heuristic, which doesn't consider the supertraits and associated types.
+    pub object_safe: bool,
+    /// The methods declared by the trait, with or without default implementation.
+    pub methods: Vec<Method>,
+}
+
+/// Represents an `extern` block with the ABI of its foreign functions and statics.
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+pub struct ForeignMod {
+    /// The ABI string, e.g. `C`, which is also the ABI of a plain `extern` block.
+    pub abi: String,
+    /// The foreign functions with their signatures and the foreign statics.
+    pub items: Vec<Method>,
+}
+
+/// Returns the JSON Schema of the [Class] model as pretty printed JSON.
+///
+/// The schema describes the output of the JSON output format and allows
+/// downstream tools to validate it or to generate bindings for it.
+pub fn json_schema() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(Class))
+        .expect("Failed to serialize JSON schema")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_schema_describes_class_and_method() {
+        let schema = json_schema();
+
+        assert!(schema.contains("\"title\": \"Class\""));
+        assert!(schema.contains("\"methods\""));
+        assert!(schema.contains("\"Method\""));
+    }
+
+    #[test]
+    fn test_class_serializes_to_json() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Person"),
+            documentation: String::from("A person.\n"),
+            fields: vec![Method {
+                name: String::from("name"),
+                documentation: String::from("The name.\n"),
+                ..Default::default()
+            }],
+            methods: Vec::new(),
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+
+        let json = serde_json::to_string(&class).unwrap();
+
+        assert!(json.contains("\"name\":\"Person\""));
+        assert!(json.contains("\"fields\":[{\"name\":\"name\""));
+    }
 }
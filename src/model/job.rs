@@ -0,0 +1,28 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli::OutputFormat;
+
+/// A single conversion job of the `--jobs-stdin` mode, read from one line of stdin.
+#[derive(Deserialize)]
+pub struct Job {
+    /// The Rust code to document.
+    pub input: String,
+    /// The output format. If not specified, the format of the command line is used.
+    pub format: Option<OutputFormat>,
+}
+
+/// The result of a [Job], written as one line to stdout.
+#[derive(Serialize, Default)]
+pub struct JobResult {
+    /// The generated content per output format.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub output: BTreeMap<OutputFormat, String>,
+    /// The warnings raised while processing the job.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// The error which prevented processing the job.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
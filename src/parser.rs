@@ -1,3 +1,183 @@
+use syn::visit_mut::VisitMut;
+use syn::{Block, Item, ItemImpl, ItemMod, Meta, NestedMeta, Stmt, Type};
+
 pub mod asciidoc_parser;
+pub mod component_diagram_parser;
+pub mod layered_diagram_parser;
+pub mod markdown_parser;
 pub mod plantuml_parser;
 pub mod rust_doc_parser;
+
+/// Removes all test modules from the items, also from nested inline modules.
+///
+/// Test modules are never part of the documented API, so they are skipped
+/// unless tests are included explicitly.
+pub fn remove_test_modules(items: &mut Vec<Item>) {
+    items.retain(|item| !matches!(item, Item::Mod(item_mod) if is_test_module(item_mod)));
+    for item in items {
+        if let Item::Mod(item_mod) = item {
+            if let Some((_, mod_items)) = &mut item_mod.content {
+                remove_test_modules(mod_items);
+            }
+        }
+    }
+}
+
+/// Collects the items of the file together with the items of its inline modules,
+/// like the ones of the module tree of a Cargo target, in the order of the source.
+pub fn module_items(items: Vec<Item>) -> Vec<Item> {
+    items
+        .into_iter()
+        .flat_map(|item| match item {
+            Item::Mod(ItemMod {
+                content: Some((_, mod_items)),
+                ..
+            }) => module_items(mod_items),
+            item => vec![item],
+        })
+        .collect()
+}
+
+/// Removes the items declared in function and method bodies, also in nested
+/// blocks and closures.
+///
+/// These items are local to their function and no part of the module, so they
+/// are neither documented nor drawn, whatever the parsers look into.
+pub fn remove_function_local_items(items: &mut [Item]) {
+    for item in items {
+        LocalItemRemover.visit_item_mut(item);
+    }
+}
+
+/// Removes the item statements of every block it visits.
+struct LocalItemRemover;
+
+impl VisitMut for LocalItemRemover {
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        block.stmts.retain(|stmt| !matches!(stmt, Stmt::Item(_)));
+        syn::visit_mut::visit_block_mut(self, block);
+    }
+}
+
+/// Returns the name of the type an impl block is for, without its path and generic
+/// arguments, so `impl<T> model::Wrapper<T>` matches `struct Wrapper<T>`.
+///
+/// Returns `None` if the self type is no path, like a tuple or slice.
+pub fn impl_type_name(item_impl: &ItemImpl) -> Option<String> {
+    match &*item_impl.self_ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns true if the module is annotated with `#[cfg(test)]`.
+pub fn is_test_module(item_mod: &ItemMod) -> bool {
+    item_mod
+        .attrs
+        .iter()
+        .any(|attribute| match attribute.parse_meta() {
+            Ok(Meta::List(meta_list)) if meta_list.path.is_ident("cfg") => {
+                meta_list.nested.iter().any(is_test_predicate)
+            }
+            _ => false,
+        })
+}
+
+/// Returns true if the `cfg` predicate is just `test`.
+fn is_test_predicate(nested: &NestedMeta) -> bool {
+    matches!(nested, NestedMeta::Meta(Meta::Path(path)) if path.is_ident("test"))
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::__private::quote::quote;
+
+    use super::*;
+
+    #[test]
+    fn test_remove_test_modules() {
+        let mut parsed_file = syn::parse_file(
+            r#"
+            struct Person {}
+            #[cfg(test)]
+            mod tests {}
+            mod model {
+                #[cfg(test)]
+                mod tests;
+                struct Order {}
+            }
+            "#,
+        )
+        .unwrap();
+
+        remove_test_modules(&mut parsed_file.items);
+
+        assert_eq!(parsed_file.items.len(), 2);
+        let Item::Mod(model) = &parsed_file.items[1] else {
+            panic!("Expected the module 'model'");
+        };
+        assert_eq!(model.content.as_ref().unwrap().1.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_function_local_items() {
+        let mut parsed_file = syn::parse_file(
+            r#"
+            struct Parser {}
+            impl Parser {
+                fn parse(&self) {
+                    struct Token {}
+                    let tokens = || {
+                        struct State {}
+                    };
+                }
+            }
+            mod model {
+                fn helper() -> u32 {
+                    const LIMIT: u32 = 1;
+                    LIMIT
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        remove_function_local_items(&mut parsed_file.items);
+
+        let code = quote!(#parsed_file).to_string();
+        assert!(code.contains("struct Parser"));
+        assert!(!code.contains("Token"));
+        assert!(!code.contains("State"));
+        assert!(!code.contains("const LIMIT"));
+        assert!(code.contains("fn helper"));
+    }
+
+    #[test]
+    fn test_impl_type_name_ignores_generic_arguments() {
+        let impl_type_names: Vec<Option<String>> = [
+            "impl Wrapper {}",
+            "impl<T: Clone> Wrapper<T> {}",
+            "impl<'a, const N: usize> model::Wrapper<'a, N> {}",
+            "impl<T> Display for Wrapper<Vec<T>> {}",
+            "impl Marker for (u8, u8) {}",
+        ]
+        .iter()
+        .map(|code| impl_type_name(&syn::parse_str(code).unwrap()))
+        .collect();
+
+        assert_eq!(
+            impl_type_names,
+            vec![
+                Some(String::from("Wrapper")),
+                Some(String::from("Wrapper")),
+                Some(String::from("Wrapper")),
+                Some(String::from("Wrapper")),
+                None
+            ]
+        );
+    }
+}
@@ -1,11 +1,34 @@
-use std::io::{Error, ErrorKind, Write};
+use std::io::{Error, ErrorKind, Read, Write};
 use std::process::{Command, Stdio};
-use std::{env, io};
+use std::time::{Duration, Instant};
+use std::{env, io, thread};
+
+/// The time pandoc may take for a conversion, unless configured otherwise.
+const DEFAULT_PANDOC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The prefix of the placeholders the verbatim Rust blocks are replaced with while
+/// pandoc converts the rest. Followed by the index of the block.
+const VERBATIM_PLACEHOLDER: &str = "RUSTITECTVERBATIMBLOCK";
+
+/// The macros whose lines are highlighted in the Rust blocks on request.
+const ASSERT_MACROS: &[&str] = &[
+    "assert!",
+    "assert_eq!",
+    "assert_ne!",
+    "debug_assert!",
+    "debug_assert_eq!",
+    "debug_assert_ne!",
+];
 
 /// Utility for parsing and converting text formats, primarily focused on converting
 /// from Markdown to AsciiDoc.
 pub struct AsciidocParser {
     pandoc_path: String,
+    diagram_fence: String,
+    timeout: Duration,
+    retry: bool,
+    verbatim_examples: bool,
+    highlight_asserts: bool,
 }
 
 impl AsciidocParser {
@@ -20,7 +43,49 @@ impl AsciidocParser {
         let pandoc_path = pandoc_path
             .unwrap_or_else(|| env::var("PANDOC_PATH").unwrap_or_else(|_| String::from("pandoc")));
 
-        AsciidocParser { pandoc_path }
+        AsciidocParser {
+            pandoc_path,
+            diagram_fence: String::from("plantuml"),
+            timeout: DEFAULT_PANDOC_TIMEOUT,
+            retry: false,
+            verbatim_examples: false,
+            highlight_asserts: false,
+        }
+    }
+
+    /// Sets the language tag of the fenced PlantUML block in the Markdown, which
+    /// is converted to an AsciiDoc `[plantuml]` block. Defaults to `plantuml`.
+    pub fn with_diagram_fence(mut self, diagram_fence: &str) -> Self {
+        self.diagram_fence = diagram_fence.to_string();
+        self
+    }
+
+    /// Sets the time pandoc may take for a conversion, after which it is killed
+    /// and an error is returned. Defaults to 30 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets whether a conversion which timed out is retried once.
+    pub fn with_retry(mut self, retry: bool) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets whether the fenced Rust blocks, like the examples, are passed through
+    /// verbatim instead of being converted by pandoc, which may reindent them or
+    /// alter their escapes.
+    pub fn with_verbatim_examples(mut self, verbatim_examples: bool) -> Self {
+        self.verbatim_examples = verbatim_examples;
+        self
+    }
+
+    /// Sets whether the lines of the assertions in the Rust blocks, like
+    /// `assert_eq!`, are highlighted, so the documented contract stands out.
+    pub fn with_highlight_asserts(mut self, highlight_asserts: bool) -> Self {
+        self.highlight_asserts = highlight_asserts;
+        self
     }
 
     /// Converts the provided Markdown text to AsciiDoc format.
@@ -45,10 +110,21 @@ impl AsciidocParser {
         &self,
         markdown_text: &str,
     ) -> Result<String, Box<dyn std::error::Error>> {
-        match self.convert_with_pandoc(markdown_text, Format::Markdown, Format::Asciidoc) {
+        let (markdown_text, verbatim_blocks) = if self.verbatim_examples {
+            extract_rust_blocks(markdown_text)
+        } else {
+            (markdown_text.to_string(), Vec::new())
+        };
+        match self.convert_with_pandoc(&markdown_text, Format::Markdown, Format::Asciidoc) {
             Ok(result) => {
-                let result = result.replace("[source,plantuml]", "[plantuml]");
-                Ok(result)
+                let source_block = format!("[source,{}]", self.diagram_fence);
+                let result = result.replace(&source_block, "[plantuml]");
+                let result = splice_rust_blocks(&result, &verbatim_blocks);
+                if self.highlight_asserts {
+                    Ok(highlight_assert_lines(&result))
+                } else {
+                    Ok(result)
+                }
             }
             Err(e) => {
                 if e.to_string().contains("program not found") {
@@ -78,11 +154,29 @@ impl AsciidocParser {
     ///
     /// * `Ok(String)` - The converted text.
     /// * `Err(io::Error)` - An error occurred during the conversion process.
+    ///
+    /// If the conversion times out and retries are enabled, it is retried once.
     fn convert_with_pandoc(
         &self,
         input: &str,
         input_format: Format,
         output_format: Format,
+    ) -> io::Result<String> {
+        match self.run_pandoc(input, &input_format, &output_format) {
+            Err(e) if e.kind() == ErrorKind::TimedOut && self.retry => {
+                self.run_pandoc(input, &input_format, &output_format)
+            }
+            result => result,
+        }
+    }
+
+    /// Runs the `pandoc` command once. The child is killed if it doesn't finish
+    /// within the timeout, which results in an error of the kind `TimedOut`.
+    fn run_pandoc(
+        &self,
+        input: &str,
+        input_format: &Format,
+        output_format: &Format,
     ) -> io::Result<String> {
         let mut child = Command::new(self.pandoc_path.as_str())
             .arg("-f")
@@ -91,26 +185,258 @@ impl AsciidocParser {
             .arg(output_format.as_str())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
             .spawn()?;
 
-        {
-            let stdin = child.stdin.as_mut().unwrap();
-            stdin.write_all(input.as_bytes())?;
-        }
+        // The pipes are served by threads, so a hanging pandoc can't block the timeout.
+        let mut stdin = child.stdin.take().unwrap();
+        let input = input.to_string();
+        let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let stdout = read_in_thread(child.stdout.take().unwrap());
+        let stderr = read_in_thread(child.stderr.take().unwrap());
 
-        let output = child.wait_with_output()?;
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                child.kill()?;
+                child.wait()?;
+                return Err(io::Error::new(
+                    ErrorKind::TimedOut,
+                    format!(
+                        "Pandoc did not finish within {} seconds",
+                        self.timeout.as_secs_f32()
+                    ),
+                ));
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        // A failed write is reported by the exit status and the error output of pandoc.
+        let _ = writer.join();
+        let stdout = stdout.join().unwrap()?;
+        let stderr = stderr.join().unwrap()?;
 
-        if output.status.success() {
-            Ok(std::str::from_utf8(&output.stdout).unwrap().to_string())
+        if status.success() {
+            Ok(String::from_utf8_lossy(&stdout).to_string())
         } else {
             Err(io::Error::new(
                 io::ErrorKind::Other,
-                std::str::from_utf8(&output.stderr).unwrap(),
+                String::from_utf8_lossy(&stderr).to_string(),
             ))
         }
     }
 }
 
+/// Replaces each fenced Rust block of the Markdown by a placeholder paragraph,
+/// which pandoc leaves alone. Like for rustdoc, a fence without language is Rust.
+///
+/// # Returns
+///
+/// The Markdown with the placeholders and the code of the replaced blocks.
+fn extract_rust_blocks(markdown_text: &str) -> (String, Vec<String>) {
+    let mut markdown = String::new();
+    let mut blocks = Vec::new();
+    let mut current_block: Option<String> = None;
+    let mut in_other_block = false;
+    for line in markdown_text.split_inclusive('\n') {
+        let is_fence = line.trim_start().starts_with("```");
+        // as in Markdown, a closing fence has no info string
+        let is_closing_fence = line.trim() == "```";
+        if let Some(block) = current_block.as_mut() {
+            if is_closing_fence {
+                markdown.push_str(&format!("\n{VERBATIM_PLACEHOLDER}{}\n\n", blocks.len()));
+                blocks.push(current_block.take().unwrap_or_default());
+            } else {
+                block.push_str(line);
+            }
+            continue;
+        }
+        if in_other_block {
+            in_other_block = !is_closing_fence;
+        } else if is_fence && is_rust_info_string(&line.trim_start()[3..]) {
+            current_block = Some(String::new());
+            continue;
+        } else {
+            in_other_block = is_fence;
+        }
+        markdown.push_str(line);
+    }
+    // an unclosed block is left to pandoc
+    if let Some(block) = current_block {
+        markdown.push_str(&format!("```rust\n{block}"));
+    }
+    (markdown, blocks)
+}
+
+/// The attributes rustdoc accepts in the info string of a Rust code block.
+const RUSTDOC_ATTRIBUTES: &[&str] = &[
+    "ignore",
+    "should_panic",
+    "no_run",
+    "compile_fail",
+    "edition2015",
+    "edition2018",
+    "edition2021",
+];
+
+/// Returns true if the info string of a fence marks a Rust block for rustdoc: it
+/// is empty, starts with `rust` or with one of the rustdoc attributes.
+fn is_rust_info_string(info_string: &str) -> bool {
+    let language = info_string
+        .trim()
+        .split(|character: char| character == ',' || character.is_whitespace())
+        .next()
+        .unwrap_or_default();
+    language.is_empty() || language.starts_with("rust") || RUSTDOC_ATTRIBUTES.contains(&language)
+}
+
+/// Replaces the placeholder paragraphs in the AsciiDoc by the verbatim Rust blocks.
+fn splice_rust_blocks(asciidoc: &str, blocks: &[String]) -> String {
+    if blocks.is_empty() {
+        return asciidoc.to_string();
+    }
+    let mut spliced = String::new();
+    for line in asciidoc.split_inclusive('\n') {
+        let block = line
+            .trim()
+            .strip_prefix(VERBATIM_PLACEHOLDER)
+            .and_then(|index| index.parse::<usize>().ok())
+            .and_then(|index| blocks.get(index));
+        match block {
+            Some(block) => spliced.push_str(&format!("[source,rust]\n----\n{block}----\n")),
+            None => spliced.push_str(line),
+        }
+    }
+    spliced
+}
+
+/// Adds the `highlight` attribute with the lines of the assertions to each Rust
+/// source block, e.g. `[source,rust,highlight=3;5..7]`. An assertion spanning
+/// several lines is highlighted up to its closing parenthesis.
+///
+/// A listing block without attributes, as pandoc renders a code block without
+/// language, is taken for Rust as well. The other delimited blocks, like the
+/// PlantUML diagrams, are left alone.
+fn highlight_assert_lines(asciidoc: &str) -> String {
+    let lines: Vec<&str> = asciidoc.split_inclusive('\n').collect();
+    let mut highlighted = String::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let is_rust_block = line.trim_end() == "[source,rust]"
+            && lines
+                .get(index + 1)
+                .is_some_and(|next| next.trim_end() == "----");
+        let is_plain_listing = line.trim_end() == "----";
+        let is_other_block = !is_rust_block
+            && line.starts_with('[')
+            && line.trim_end().ends_with(']')
+            && lines
+                .get(index + 1)
+                .is_some_and(|next| next.trim_end() == "----");
+        if !is_rust_block && !is_plain_listing && !is_other_block {
+            highlighted.push_str(line);
+            index += 1;
+            continue;
+        }
+        let delimiter = if is_plain_listing { index } else { index + 1 };
+        let body_start = delimiter + 1;
+        let body_end = lines[body_start..]
+            .iter()
+            .position(|line| line.trim_end() == "----")
+            .map_or(lines.len(), |position| body_start + position);
+        let ranges = if is_other_block {
+            Vec::new()
+        } else {
+            assert_line_ranges(&lines[body_start..body_end])
+        };
+        if !ranges.is_empty() {
+            highlighted.push_str(&format!("[source,rust,highlight={}]\n", ranges.join(";")));
+        } else if !is_plain_listing {
+            highlighted.push_str(line);
+        }
+        // the closing delimiter is copied as well, so it isn't taken for an opening one
+        let block_end = (body_end + 1).min(lines.len());
+        for line in &lines[delimiter..block_end] {
+            highlighted.push_str(line);
+        }
+        index = block_end;
+    }
+    highlighted
+}
+
+/// Collects the 1-based line ranges of the assertions in the code lines, like
+/// `3` or `5..7`.
+fn assert_line_ranges(code_lines: &[&str]) -> Vec<String> {
+    let mut ranges = Vec::new();
+    let mut line_number = 0;
+    while line_number < code_lines.len() {
+        let code = code_lines[line_number].trim_start();
+        if !ASSERT_MACROS.iter().any(|assert| code.starts_with(assert)) {
+            line_number += 1;
+            continue;
+        }
+        let mut depth = 0;
+        let mut end = line_number;
+        for (offset, line) in code_lines[line_number..].iter().enumerate() {
+            depth += parenthesis_depth(line);
+            end = line_number + offset;
+            if depth <= 0 {
+                break;
+            }
+        }
+        if end == line_number {
+            ranges.push(format!("{}", line_number + 1));
+        } else {
+            ranges.push(format!("{}..{}", line_number + 1, end + 1));
+        }
+        line_number = end + 1;
+    }
+    ranges
+}
+
+/// Counts the opening parentheses of the line of code minus the closing ones,
+/// leaving out the ones in string and character literals and in comments.
+fn parenthesis_depth(line: &str) -> i64 {
+    let mut depth = 0;
+    let mut characters = line.chars().peekable();
+    while let Some(character) = characters.next() {
+        match character {
+            '"' => {
+                while let Some(character) = characters.next() {
+                    match character {
+                        '\\' => {
+                            characters.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            // a character literal, not a lifetime
+            '\'' if characters.clone().nth(1) == Some('\'') => {
+                characters.nth(1);
+            }
+            '/' if characters.peek() == Some(&'/') => break,
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// Reads the pipe to its end in a thread of its own.
+fn read_in_thread(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        pipe.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    })
+}
+
 /// `Format` is an enum that represents the supported text formats for
 /// the [convert_with_pandoc] function.
 #[derive(Debug)]
@@ -143,6 +469,81 @@ mod tests {
         assert_eq!(result_text, "== Title\n\n=== Subtitle\n\nSome text\n");
     }
 
+    #[test]
+    fn test_parse_from_markdown_keeps_rust_blocks_verbatim() {
+        let code =
+            "let text = \"tab\\t and \\\"quote\\\"\";\n    let v: Vec<*const u8> = vec![];\n";
+        let markdown_text = format!("# Examples\n\n```rust\n{code}```\n\nSome *text*\n");
+
+        let parser = AsciidocParser::new(None).with_verbatim_examples(true);
+        let result = parser.parse_from_markdown(&markdown_text).unwrap();
+
+        assert!(result.contains(&format!("[source,rust]\n----\n{code}----\n")));
+        assert!(!result.contains(VERBATIM_PLACEHOLDER));
+    }
+
+    #[test]
+    fn test_highlight_assert_lines() {
+        let asciidoc = "[source,rust]\n----\nlet sum = add(1, 2);\nassert_eq!(sum, 3);\n\
+            assert!(\n    is_valid(sum)\n);\n----\n\n[source,rust]\n----\nlet a = 1;\n----\n";
+
+        assert_eq!(
+            highlight_assert_lines(asciidoc),
+            "[source,rust,highlight=2;3..5]\n----\nlet sum = add(1, 2);\nassert_eq!(sum, 3);\n\
+            assert!(\n    is_valid(sum)\n);\n----\n\n[source,rust]\n----\nlet a = 1;\n----\n"
+        );
+    }
+
+    #[test]
+    fn test_highlight_assert_lines_of_plain_listings_only() {
+        let asciidoc =
+            "----\nassert!(\n    text.ends_with(\")\")\n);\nassert_eq!(c, '(');\n----\n\n\
+            [plantuml]\n----\nassert!(x)\n----\n";
+
+        assert_eq!(
+            highlight_assert_lines(asciidoc),
+            "[source,rust,highlight=1..3;4]\n----\nassert!(\n    text.ends_with(\")\")\n);\n\
+            assert_eq!(c, '(');\n----\n\n[plantuml]\n----\nassert!(x)\n----\n"
+        );
+    }
+
+    #[test]
+    fn test_parenthesis_depth_skips_literals_and_comments() {
+        assert_eq!(parenthesis_depth(r#"assert!(text.contains("(")"#), 1);
+        assert_eq!(parenthesis_depth(r#"assert!(s == "\")", "#), 1);
+        assert_eq!(parenthesis_depth("assert_eq!(c, ')'); // (x"), 0);
+        assert_eq!(parenthesis_depth("fn get<'a>(&'a self) -> &'a str {"), 0);
+    }
+
+    #[test]
+    fn test_extract_rust_blocks_skips_other_blocks() {
+        let markdown_text = "```text\n```rust\n```\n```rust,ignore\nlet a = 1;\n```\n";
+
+        let (markdown, blocks) = extract_rust_blocks(markdown_text);
+
+        assert_eq!(
+            markdown,
+            format!("```text\n```rust\n```\n\n{VERBATIM_PLACEHOLDER}0\n\n")
+        );
+        assert_eq!(blocks, vec![String::from("let a = 1;\n")]);
+    }
+
+    #[test]
+    fn test_extract_rust_blocks_takes_bare_fences_for_rust() {
+        let markdown_text = "```\nlet a = 1;\n```\n```no_run\nlet b = 2;\n```\n";
+
+        let (markdown, blocks) = extract_rust_blocks(markdown_text);
+
+        assert_eq!(
+            markdown,
+            format!("\n{VERBATIM_PLACEHOLDER}0\n\n\n{VERBATIM_PLACEHOLDER}1\n\n")
+        );
+        assert_eq!(
+            blocks,
+            vec![String::from("let a = 1;\n"), String::from("let b = 2;\n")]
+        );
+    }
+
     #[test]
     fn test_parse_from_markdown_error() {
         //Save the environment variable PANDOC_PATH before changing it
@@ -153,4 +554,27 @@ mod tests {
         let result = parser.parse_from_markdown(markdown_text);
         assert!(result.is_err());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_from_markdown_times_out() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script_path = env::temp_dir().join(format!(
+            "rustitect_hanging_pandoc_{}.sh",
+            std::process::id()
+        ));
+        std::fs::write(&script_path, "#!/bin/sh\nsleep 5\n").unwrap();
+        std::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let parser = AsciidocParser::new(Some(script_path.to_string_lossy().to_string()))
+            .with_timeout(Duration::from_millis(200));
+        let started = Instant::now();
+        let result = parser.parse_from_markdown("# Title");
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("Pandoc did not finish within"));
+        std::fs::remove_file(script_path).unwrap();
+    }
 }
@@ -0,0 +1,201 @@
+use syn::{Item, UseTree};
+
+use crate::parser::remove_test_modules;
+
+/// Represents a parser for generating a PlantUML component diagram of the module
+/// structure of Rust source code.
+///
+/// Each module becomes a component and the `use` declarations of a module become
+/// dependency arrows to the modules they import from. Together with the module
+/// tree of a Cargo target this gives the building block view of a crate.
+#[derive(Default)]
+pub struct ComponentDiagramParser {
+    /// The raw Rust source code, with the modules of other files inlined.
+    pub(crate) raw_rust_code: String,
+    /// Whether `#[cfg(test)]` modules are part of the diagram.
+    pub(crate) include_tests: bool,
+}
+
+impl ComponentDiagramParser {
+    /// Parses Rust source code and generates a PlantUML component diagram.
+    ///
+    /// Imports are resolved relative to the crate root (`crate::`), the parent
+    /// module (`super::`), the current module (`self::`) and, for plain paths, the
+    /// current module and the crate root. Imports of other crates are ignored.
+    ///
+    /// # Returns
+    ///
+    /// A string containing the PlantUML component diagram.
+    pub fn parse_code_to_string(&self) -> String {
+        let mut parsed_file =
+            syn::parse_file(self.raw_rust_code.as_str()).expect("Unable to parse file");
+        if !self.include_tests {
+            remove_test_modules(&mut parsed_file.items);
+        }
+        let mut modules = Vec::new();
+        collect_modules(&parsed_file.items, &mut Vec::new(), &mut modules);
+        let module_paths: Vec<&Vec<String>> = modules.iter().map(|module| &module.path).collect();
+
+        let mut dependencies: Vec<(String, String)> = Vec::new();
+        for module in &modules {
+            for import in &module.imports {
+                let Some(target) = resolve_import(&module.path, import, &module_paths) else {
+                    continue;
+                };
+                let dependency = (component_name(&module.path), component_name(&target));
+                if target != module.path && !dependencies.contains(&dependency) {
+                    dependencies.push(dependency);
+                }
+            }
+        }
+
+        let mut plantuml = String::from("@startuml\n\n");
+        for module in &modules {
+            let name = component_name(&module.path);
+            plantuml.push_str(&format!("component \"{name}\" as {}\n", alias(&name)));
+        }
+        if !dependencies.is_empty() {
+            plantuml.push('\n');
+            for (from, to) in &dependencies {
+                plantuml.push_str(&format!("{} --> {}\n", alias(from), alias(to)));
+            }
+        }
+        plantuml.push_str("\n@enduml");
+        plantuml
+    }
+}
+
+/// A module of the code with the paths it imports with `use` declarations.
+struct ModuleInfo {
+    path: Vec<String>,
+    imports: Vec<Vec<String>>,
+}
+
+/// Collects the crate root and all inline modules with their imports.
+fn collect_modules(items: &[Item], module_path: &mut Vec<String>, modules: &mut Vec<ModuleInfo>) {
+    let mut imports = Vec::new();
+    for item in items {
+        if let Item::Use(item_use) = item {
+            flatten_use_tree(&item_use.tree, &mut Vec::new(), &mut imports);
+        }
+    }
+    modules.push(ModuleInfo {
+        path: module_path.clone(),
+        imports,
+    });
+    for item in items {
+        if let Item::Mod(item_mod) = item {
+            if let Some((_, mod_items)) = &item_mod.content {
+                module_path.push(item_mod.ident.to_string());
+                collect_modules(mod_items, module_path, modules);
+                module_path.pop();
+            }
+        }
+    }
+}
+
+/// Flattens a use tree like `crate::model::{job::Job, Class}` into its full paths.
+fn flatten_use_tree(tree: &UseTree, prefix: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+    match tree {
+        UseTree::Path(use_path) => {
+            prefix.push(use_path.ident.to_string());
+            flatten_use_tree(&use_path.tree, prefix, paths);
+            prefix.pop();
+        }
+        UseTree::Name(use_name) => {
+            paths.push([prefix.clone(), vec![use_name.ident.to_string()]].concat())
+        }
+        UseTree::Rename(use_rename) => {
+            paths.push([prefix.clone(), vec![use_rename.ident.to_string()]].concat())
+        }
+        UseTree::Glob(_) => paths.push(prefix.clone()),
+        UseTree::Group(use_group) => {
+            for tree in &use_group.items {
+                flatten_use_tree(tree, prefix, paths);
+            }
+        }
+    }
+}
+
+/// Resolves an imported path to the module it imports from, which is the longest
+/// prefix of the path naming a module of the code.
+fn resolve_import(
+    module_path: &[String],
+    import: &[String],
+    module_paths: &[&Vec<String>],
+) -> Option<Vec<String>> {
+    let (bases, relative) = match import.first().map(String::as_str) {
+        Some("crate") => (vec![Vec::new()], &import[1..]),
+        Some("self") => (vec![module_path.to_vec()], &import[1..]),
+        Some("super") => {
+            let parent = module_path[..module_path.len().saturating_sub(1)].to_vec();
+            (vec![parent], &import[1..])
+        }
+        _ => (vec![module_path.to_vec(), Vec::new()], import),
+    };
+    for base in bases {
+        for length in (1..=relative.len()).rev() {
+            let candidate = [base.clone(), relative[..length].to_vec()].concat();
+            if module_paths.contains(&&candidate) {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+/// Returns the name of the component of a module, `crate` for the crate root.
+fn component_name(module_path: &[String]) -> String {
+    if module_path.is_empty() {
+        String::from("crate")
+    } else {
+        module_path.join("::")
+    }
+}
+
+/// Returns the PlantUML alias of a component, as `::` is not allowed in aliases.
+fn alias(component_name: &str) -> String {
+    component_name.replace("::", "__")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_code_to_string_draws_module_dependencies() {
+        let rust_code = String::from(
+            r#"
+            use std::collections::HashMap;
+            mod model {
+                pub mod job {
+                    use super::class_object::Class;
+                }
+                pub mod class_object {}
+            }
+            mod processing {
+                use crate::model::{job::Job, class_object};
+                use std::fmt;
+            }
+            "#,
+        );
+        let expected_puml = "@startuml\n\n\
+            component \"crate\" as crate\n\
+            component \"model\" as model\n\
+            component \"model::job\" as model__job\n\
+            component \"model::class_object\" as model__class_object\n\
+            component \"processing\" as processing\n\n\
+            model__job --> model__class_object\n\
+            processing --> model__job\n\
+            processing --> model__class_object\n\n\
+            @enduml";
+
+        let parser = ComponentDiagramParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let actual_puml = parser.parse_code_to_string();
+
+        assert_eq!(actual_puml, expected_puml);
+    }
+}
@@ -0,0 +1,201 @@
+use syn::visit::Visit;
+use syn::{Item, TypePath};
+
+use crate::parser::remove_test_modules;
+
+/// Represents a parser for generating a layered overview diagram of Rust source code.
+///
+/// In contrast to the [PlantumlParser](super::plantuml_parser::PlantumlParser), which
+/// renders every type with all of its members, the `LayeredDiagramParser` only shows
+/// the type names grouped into one PlantUML package per module. Relationships between
+/// the types are drawn as arrows, also across module boundaries, which gives a
+/// structured overview of the code.
+#[derive(Default)]
+pub struct LayeredDiagramParser {
+    /// The raw Rust source code that will be parsed into a layered diagram.
+    pub(crate) raw_rust_code: String,
+    /// Whether `#[cfg(test)]` modules are part of the diagram.
+    pub(crate) include_tests: bool,
+}
+
+impl LayeredDiagramParser {
+    /// Parses Rust source code and generates a layered PlantUML diagram.
+    ///
+    /// Types declared at the top level of the file are rendered outside of any
+    /// package, types declared in inline modules are rendered inside a package named
+    /// after the module path (e.g. `model::order`). A field referencing another type
+    /// of the file results in an association arrow between both types.
+    ///
+    /// # Returns
+    ///
+    /// A string containing the layered PlantUML diagram.
+    pub fn parse_code_to_string(&self) -> String {
+        let mut parsed_file =
+            syn::parse_file(self.raw_rust_code.as_str()).expect("Unable to parse file");
+        if !self.include_tests {
+            remove_test_modules(&mut parsed_file.items);
+        }
+        let mut types = Vec::new();
+        collect_types(&parsed_file.items, &mut Vec::new(), &mut types);
+
+        let mut plantuml = String::from("@startuml\n\n");
+        let mut layers: Vec<&String> = Vec::new();
+        for type_info in &types {
+            if !layers.contains(&&type_info.module) {
+                layers.push(&type_info.module);
+            }
+        }
+        for layer in layers {
+            let class_lines = types
+                .iter()
+                .filter(|type_info| &type_info.module == layer)
+                .map(|type_info| format!("class \"{}\"\n", type_info.name))
+                .collect::<String>();
+            if layer.is_empty() {
+                plantuml.push_str(&format!("{class_lines}\n"));
+            } else {
+                let indented = class_lines
+                    .lines()
+                    .map(|line| format!("    {line}\n"))
+                    .collect::<String>();
+                plantuml.push_str(&format!("package \"{layer}\" {{\n{indented}}}\n\n"));
+            }
+        }
+
+        let type_names: Vec<&String> = types.iter().map(|type_info| &type_info.name).collect();
+        let mut relationships = Vec::new();
+        for type_info in &types {
+            for reference in &type_info.references {
+                let relationship = format!("\"{}\" --> \"{}\"\n", type_info.name, reference);
+                if reference != &type_info.name
+                    && type_names.contains(&reference)
+                    && !relationships.contains(&relationship)
+                {
+                    relationships.push(relationship);
+                }
+            }
+        }
+        if !relationships.is_empty() {
+            plantuml.push_str(&format!("{}\n", relationships.concat()));
+        }
+
+        plantuml.push_str("@enduml");
+        plantuml
+    }
+}
+
+/// A type found in the code, together with its module and the types it references.
+struct TypeInfo {
+    module: String,
+    name: String,
+    references: Vec<String>,
+}
+
+/// Collects all structs and enums of the given items, descending into inline modules.
+fn collect_types(items: &[Item], module_path: &mut Vec<String>, types: &mut Vec<TypeInfo>) {
+    for item in items {
+        match item {
+            Item::Struct(item_struct) => {
+                let mut collector = TypeReferenceCollector::default();
+                collector.visit_fields(&item_struct.fields);
+                types.push(TypeInfo {
+                    module: module_path.join("::"),
+                    name: item_struct.ident.to_string(),
+                    references: collector.references,
+                });
+            }
+            Item::Enum(item_enum) => {
+                let mut collector = TypeReferenceCollector::default();
+                for variant in &item_enum.variants {
+                    collector.visit_fields(&variant.fields);
+                }
+                types.push(TypeInfo {
+                    module: module_path.join("::"),
+                    name: item_enum.ident.to_string(),
+                    references: collector.references,
+                });
+            }
+            Item::Mod(item_mod) => {
+                if let Some((_, mod_items)) = &item_mod.content {
+                    module_path.push(item_mod.ident.to_string());
+                    collect_types(mod_items, module_path, types);
+                    module_path.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collects the names of all types referenced within the visited syntax tree.
+#[derive(Default)]
+struct TypeReferenceCollector {
+    references: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for TypeReferenceCollector {
+    fn visit_type_path(&mut self, type_path: &'ast TypePath) {
+        if let Some(segment) = type_path.path.segments.last() {
+            self.references.push(segment.ident.to_string());
+        }
+        syn::visit::visit_type_path(self, type_path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_code_to_string_groups_types_by_module() {
+        let rust_code = String::from(
+            r#"
+            struct Shop { orders: Vec<model::Order> }
+            mod model {
+                pub struct Order { customer: Customer, amount: u64 }
+                pub struct Customer { name: String }
+            }
+            "#,
+        );
+        let expected_puml = "@startuml\n\n\
+            class \"Shop\"\n\n\
+            package \"model\" {\n    class \"Order\"\n    class \"Customer\"\n}\n\n\
+            \"Shop\" --> \"Order\"\n\"Order\" --> \"Customer\"\n\n\
+            @enduml";
+
+        let parser = LayeredDiagramParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let actual_puml = parser.parse_code_to_string();
+
+        assert_eq!(actual_puml, expected_puml);
+    }
+
+    #[test]
+    fn test_parse_code_to_string_skips_test_modules() {
+        let rust_code = String::from(
+            r#"
+            struct Person {}
+            #[cfg(test)]
+            mod tests {
+                struct Fixture {}
+            }
+            "#,
+        );
+
+        let parser = LayeredDiagramParser {
+            raw_rust_code: rust_code.clone(),
+            ..Default::default()
+        };
+        let including_parser = LayeredDiagramParser {
+            raw_rust_code: rust_code,
+            include_tests: true,
+        };
+
+        assert!(!parser.parse_code_to_string().contains("Fixture"));
+        assert!(including_parser
+            .parse_code_to_string()
+            .contains("package \"tests\""));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::model::class_object::{Class, Method};
+
+/// Parser for reading back the Markdown generated by Rustitect into a [Class].
+///
+/// This is the inverse of the Markdown rendering and only as precise as the
+/// Markdown structure allows: the level two heading is the class name, level three
+/// headings are fields or, if they contain a parameter list, methods. The level four
+/// headings below a `Builder Methods` heading are builder methods. Everything in
+/// between is treated as documentation of the preceding heading.
+pub struct MarkdownParser {
+    /// The Markdown generated from a Rust source file.
+    pub(crate) raw_markdown: String,
+}
+
+impl MarkdownParser {
+    /// Parses the Markdown and returns the represented `Class`.
+    ///
+    /// Fenced code blocks are skipped when looking for headings, so headings
+    /// within examples or the PlantUML block do not disturb the structure.
+    ///
+    /// # Returns
+    ///
+    /// A `Class` instance with name, fields and methods found in the Markdown.
+    pub fn parse_to_class(&self) -> Class {
+        let mut class = Class {
+            plantuml: String::new(),
+            name: String::new(),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let mut current_member: Option<Method> = None;
+        let mut in_code_block = false;
+        let mut in_constants = false;
+        let mut in_skipped_section = false;
+        let mut in_builder_methods = false;
+
+        for line in self.raw_markdown.lines() {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+            if in_skipped_section && in_code_block {
+                continue;
+            }
+            if !in_code_block {
+                if let Some(name) = line.strip_prefix("## ") {
+                    push_member(&mut class, current_member.take(), in_constants);
+                    in_constants = name.trim() == "Constants";
+                    in_skipped_section = is_skipped_section(name.trim());
+                    in_builder_methods = false;
+                    if !in_constants && !in_skipped_section {
+                        class.name = name.trim().to_string();
+                    }
+                    continue;
+                }
+                if in_skipped_section {
+                    continue;
+                }
+                if let Some(name) = line.strip_prefix("### ") {
+                    push_member(&mut class, current_member.take(), in_constants);
+                    in_builder_methods = name.trim() == "Builder Methods";
+                    if in_builder_methods {
+                        continue;
+                    }
+                    current_member = Some(Method {
+                        name: name.trim().to_string(),
+                        documentation: String::new(),
+                        ..Default::default()
+                    });
+                    continue;
+                }
+                if let (true, Some(name)) = (in_builder_methods, line.strip_prefix("#### ")) {
+                    push_member(&mut class, current_member.take(), in_constants);
+                    current_member = Some(Method {
+                        name: name.trim().to_string(),
+                        documentation: String::new(),
+                        builder: true,
+                        ..Default::default()
+                    });
+                    continue;
+                }
+            }
+            match current_member.as_mut() {
+                Some(member) => push_line(&mut member.documentation, line),
+                None => push_line(&mut class.documentation, line),
+            }
+        }
+        push_member(&mut class, current_member, in_constants);
+        class
+    }
+}
+
+/// Adds the member to the constants of the class if it is in the constants section,
+/// to the methods if its name has a parameter list, otherwise to the fields.
+///
+/// A field rendered as optional, like `name (optional): String`, gets its name and
+/// inner type back.
+fn push_member(class: &mut Class, member: Option<Method>, in_constants: bool) {
+    if let Some(mut member) = member {
+        let has_parameter_list = member
+            .name
+            .split_whitespace()
+            .next()
+            .is_some_and(|name| name.contains('('));
+        if in_constants {
+            class.constants.push(member);
+        } else if has_parameter_list {
+            class.methods.push(member);
+        } else {
+            if let Some((name, inner_type)) = member.name.split_once(" (optional): ") {
+                member.optional_type = Some(inner_type.to_string());
+                member.name = name.to_string();
+            }
+            class.fields.push(member);
+        }
+    }
+}
+
+/// Returns true if the section of the rendered Markdown doesn't contain members
+/// of the class, like the trait implementations, the traits defined in the
+/// module, the re-exports or the foreign items of an `extern` block.
+fn is_skipped_section(heading: &str) -> bool {
+    heading == "Trait Implementations"
+        || heading == "Traits"
+        || heading == "Re-exports"
+        || heading.starts_with("Foreign (extern ")
+}
+
+/// Appends a line to the documentation, skipping leading empty lines.
+fn push_line(documentation: &mut String, line: &str) {
+    if !documentation.is_empty() || !line.trim().is_empty() {
+        documentation.push_str(line);
+        documentation.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_to_class_names_and_members() {
+        let markdown = String::from(
+            "## Person\n```plantuml\n### not a heading\n```\n\nA person.\n\n\
+            ### name\nThe name.\n\n### new(name: String)\nCreates a person.\n\
+            #### Example\n```\n### still not a heading\n```\n",
+        );
+
+        let parser = MarkdownParser {
+            raw_markdown: markdown,
+        };
+        let class = parser.parse_to_class();
+
+        assert_eq!(class.name, "Person");
+        assert_eq!(class.fields.len(), 1);
+        assert_eq!(class.fields[0].name, "name");
+        assert_eq!(class.methods.len(), 1);
+        assert_eq!(class.methods[0].name, "new(name: String)");
+        assert!(class.methods[0].documentation.contains("#### Example"));
+    }
+
+    #[test]
+    fn test_parse_to_class_constants_section() {
+        let markdown = String::from(
+            "## Config\n\n### retries\n\n## Constants\n\n### MAX: usize = 100\nThe maximum.\n",
+        );
+
+        let parser = MarkdownParser {
+            raw_markdown: markdown,
+        };
+        let class = parser.parse_to_class();
+
+        assert_eq!(class.name, "Config");
+        assert_eq!(class.fields.len(), 1);
+        assert_eq!(class.constants.len(), 1);
+        assert_eq!(class.constants[0].name, "MAX: usize = 100");
+    }
+
+    #[test]
+    fn test_parse_to_class_skips_foreign_section() {
+        let markdown = String::from(
+            "## Library\n\n### name\n\n## Foreign (extern \"C\")\n\n### checksum(length: usize) -> u32\n",
+        );
+
+        let parser = MarkdownParser {
+            raw_markdown: markdown,
+        };
+        let class = parser.parse_to_class();
+
+        assert_eq!(class.name, "Library");
+        assert_eq!(class.fields.len(), 1);
+        assert!(class.methods.is_empty());
+    }
+
+    #[test]
+    fn test_parse_to_class_optional_field() {
+        let markdown =
+            String::from("## Person\n\n### nickname (optional): String\nThe nickname.\n");
+
+        let parser = MarkdownParser {
+            raw_markdown: markdown,
+        };
+        let class = parser.parse_to_class();
+
+        assert!(class.methods.is_empty());
+        assert_eq!(class.fields[0].name, "nickname");
+        assert_eq!(class.fields[0].optional_type.as_deref(), Some("String"));
+    }
+
+    #[test]
+    fn test_parse_to_class_builder_methods() {
+        let markdown = String::from(
+            "## Request
+
+### send()
+
+### Builder Methods
+
+#### new()
+Creates a request.
+",
+        );
+
+        let parser = MarkdownParser {
+            raw_markdown: markdown,
+        };
+        let class = parser.parse_to_class();
+
+        assert!(class.fields.is_empty());
+        assert_eq!(class.methods.len(), 2);
+        assert!(!class.methods[0].builder);
+        assert_eq!(class.methods[1].name, "new()");
+        assert!(class.methods[1].builder);
+        assert_eq!(class.methods[1].documentation, "Creates a request.\n");
+    }
+}
@@ -1,4 +1,15 @@
+use regex::Regex;
 use ruml::file_parser;
+use syn::__private::quote::quote;
+use syn::{
+    Attribute, Fields, GenericParam, Generics, ImplItem, Item, Lit, Meta, NestedMeta,
+    TraitBoundModifier, TypeParamBound, Visibility, WherePredicate,
+};
+
+use crate::cli::StructKeyword;
+use crate::parser::{
+    impl_type_name, module_items, remove_function_local_items, remove_test_modules,
+};
 
 /// Represents a parser for converting Rust source code into a format that can be
 /// visualized using PlantUML.
@@ -7,9 +18,34 @@ use ruml::file_parser;
 /// to generate a PlantUML-compatible representation. This representation
 /// can then be used to generate UML diagrams, giving a visual representation
 /// of the Rust source code.
+#[derive(Default)]
 pub struct PlantumlParser {
     /// The raw Rust source code that will be parsed into a PlantUML-compatible format.
     pub(crate) raw_rust_code: String,
+    /// The attribute name or doc comment marker a type's stereotype is read from.
+    pub(crate) stereotype_from: Option<String>,
+    /// Whether dependency arrows are drawn from generic types to the traits bounding them.
+    pub(crate) bound_arrows: bool,
+    /// Whether traits of the standard library are left out of the bound arrows.
+    pub(crate) exclude_std_bounds: bool,
+    /// Whether `#[cfg(test)]` modules are part of the diagram.
+    pub(crate) include_tests: bool,
+    /// The keyword structs are drawn with, `class` by default.
+    pub(crate) struct_keyword: StructKeyword,
+    /// Whether the members of each class are grouped by their visibility.
+    pub(crate) group_by_visibility: bool,
+    /// The maximum length of the notes showing the field documentation, no notes if `None`.
+    pub(crate) field_notes: Option<usize>,
+    /// The length the types of the members are abbreviated to, not abbreviated if `None`.
+    pub(crate) abbreviate_types: Option<usize>,
+    /// The namespace the diagram is wrapped in, none if `None`.
+    pub(crate) namespace: Option<String>,
+    /// Whether a legend explains the symbols and stereotypes used in the diagram.
+    pub(crate) legend: bool,
+    /// Whether the types of inline modules are grouped into a package per module.
+    pub(crate) package_by_module: bool,
+    /// Whether a note lists the types of the const generic parameters of each type.
+    pub(crate) const_generics_note: bool,
 }
 impl PlantumlParser {
     /// Parses Rust source code and generates a PlantUML string representation.
@@ -36,12 +72,986 @@ impl PlantumlParser {
     /// The above example reads a Rust source file, passes its contents to
     /// `parse_to_string`, and then prints the resulting PlantUML string.
     pub fn parse_code_to_string(&self) -> String {
-        let entities = file_parser(
-            syn::parse_file(self.raw_rust_code.as_str()).expect("Unable to parse file"),
-        );
+        let mut parsed_file =
+            syn::parse_file(self.raw_rust_code.as_str()).expect("Unable to parse file");
+        if !self.include_tests {
+            remove_test_modules(&mut parsed_file.items);
+        }
+        remove_function_local_items(&mut parsed_file.items);
+        let mut type_modules = Vec::new();
+        if self.package_by_module {
+            collect_type_modules(&parsed_file.items, &mut Vec::new(), &mut type_modules);
+        }
+        // the types of the inline modules are drawn as well, in the order of the source
+        parsed_file.items = module_items(parsed_file.items);
+        let stereotypes = match &self.stereotype_from {
+            Some(marker) => collect_stereotypes(&parsed_file.items, marker),
+            None => Vec::new(),
+        };
+        let bounds = if self.bound_arrows {
+            collect_trait_bounds(&parsed_file.items, self.exclude_std_bounds)
+        } else {
+            Vec::new()
+        };
+        let field_notes = match self.field_notes {
+            Some(max_length) => collect_field_notes(&parsed_file.items, max_length),
+            None => Vec::new(),
+        };
+        let generic_types = collect_generic_types(&parsed_file.items);
+        let structs = collect_structs_drawn_as_struct(&parsed_file.items, &self.struct_keyword);
+        let restricted_members = collect_restricted_members(&parsed_file.items);
+        let entities = file_parser(parsed_file);
+
+        let mut plantuml = ruml::render_plantuml(entities);
+        plantuml = mark_restricted_members(&plantuml, &restricted_members);
+        for struct_name in &structs {
+            plantuml = use_struct_keyword(&plantuml, struct_name);
+        }
+        for (type_name, stereotype) in &stereotypes {
+            plantuml = add_stereotype(&plantuml, type_name, stereotype);
+        }
+        for generic_type in &generic_types {
+            plantuml = add_generic_parameters(&plantuml, generic_type);
+        }
+        if let Some(max_length) = self.abbreviate_types {
+            plantuml = abbreviate_member_types(&plantuml, max_length);
+        }
+        if self.group_by_visibility {
+            plantuml = group_members_by_visibility(&plantuml);
+        }
+        let legend = if self.legend {
+            self.legend_entries(&plantuml, &stereotypes, &bounds, &field_notes)
+        } else {
+            Vec::new()
+        };
+        plantuml = add_field_notes(&plantuml, &field_notes);
+        plantuml = add_bound_arrows(&plantuml, &bounds);
+        if self.const_generics_note {
+            plantuml = add_const_generics_notes(&plantuml, &generic_types);
+        }
+        plantuml = group_into_packages(&plantuml, &type_modules);
+        if let Some(namespace) = &self.namespace {
+            plantuml = wrap_in_namespace(&plantuml, namespace);
+        }
+        add_legend(&plantuml, &legend)
+    }
+
+    /// Collects the symbols and stereotypes used in the diagram with their meaning,
+    /// in the order of the legend. Symbols which don't occur are left out.
+    fn legend_entries(
+        &self,
+        plantuml: &str,
+        stereotypes: &[(String, String)],
+        bounds: &[(String, String)],
+        field_notes: &[(String, String, String)],
+    ) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        let visibilities = [
+            ('+', "public"),
+            ('~', "visible in the crate or a module, like `pub(crate)`"),
+            ('-', "private"),
+        ];
+        for (symbol, meaning) in visibilities {
+            let used = plantuml
+                .lines()
+                .any(|line| line.trim_start().starts_with(&format!("{symbol} ")));
+            if used {
+                // `~` is the escape character of PlantUML's Creole markup
+                let symbol = if symbol == '~' {
+                    String::from("~~")
+                } else {
+                    symbol.to_string()
+                };
+                entries.push((symbol, meaning.to_string()));
+            }
+        }
+        if plantuml.lines().any(|line| line.starts_with("struct ")) {
+            let meaning = match self.struct_keyword {
+                StructKeyword::Auto => "struct without methods",
+                _ => "struct",
+            };
+            entries.push((String::from("struct"), meaning.to_string()));
+        }
+        let mut stereotype_names: Vec<&str> = Vec::new();
+        for (_, stereotype) in stereotypes {
+            if !stereotype_names.contains(&stereotype.as_str()) {
+                stereotype_names.push(stereotype);
+            }
+        }
+        if let Some(marker) = &self.stereotype_from {
+            for stereotype in stereotype_names {
+                entries.push((
+                    format!("«{stereotype}»"),
+                    format!("stereotype given by '{marker}'"),
+                ));
+            }
+        }
+        if !bounds.is_empty() {
+            entries.push((
+                String::from("..>"),
+                String::from("generic parameter bounded by the trait"),
+            ));
+        }
+        if !field_notes.is_empty() {
+            entries.push((
+                String::from("note"),
+                String::from("documentation of the field"),
+            ));
+        }
+        entries
+    }
+}
+
+/// Traits of the standard library, which are left out of the bound arrows on request.
+const STD_TRAITS: &[&str] = &[
+    "AsMut",
+    "AsRef",
+    "Clone",
+    "Copy",
+    "Debug",
+    "Default",
+    "Display",
+    "Eq",
+    "Fn",
+    "FnMut",
+    "FnOnce",
+    "From",
+    "Hash",
+    "Into",
+    "IntoIterator",
+    "Iterator",
+    "Ord",
+    "PartialEq",
+    "PartialOrd",
+    "Send",
+    "Sized",
+    "Sync",
+    "ToString",
+];
+
+/// Collects the traits bounding the generic parameters of all structs and enums,
+/// given inline (`Cache<K: Hash>`) or in a where clause.
+///
+/// # Returns
+///
+/// A list of `(type name, trait name)` pairs without duplicates in declaration order.
+fn collect_trait_bounds(items: &[Item], exclude_std_bounds: bool) -> Vec<(String, String)> {
+    let mut bounds = Vec::new();
+    for item in items {
+        let (ident, generics) = match item {
+            Item::Struct(item_struct) => (&item_struct.ident, &item_struct.generics),
+            Item::Enum(item_enum) => (&item_enum.ident, &item_enum.generics),
+            _ => continue,
+        };
+        let inline_bounds = generics.type_params().flat_map(|param| param.bounds.iter());
+        let where_bounds = generics
+            .where_clause
+            .iter()
+            .flat_map(|where_clause| where_clause.predicates.iter())
+            .filter_map(|predicate| match predicate {
+                WherePredicate::Type(predicate_type) => Some(predicate_type.bounds.iter()),
+                _ => None,
+            })
+            .flatten();
+        for bound in inline_bounds.chain(where_bounds) {
+            let TypeParamBound::Trait(trait_bound) = bound else {
+                continue;
+            };
+            if matches!(trait_bound.modifier, TraitBoundModifier::Maybe(_)) {
+                continue;
+            }
+            let Some(segment) = trait_bound.path.segments.last() else {
+                continue;
+            };
+            let trait_name = segment.ident.to_string();
+            if exclude_std_bounds && STD_TRAITS.contains(&trait_name.as_str()) {
+                continue;
+            }
+            let pair = (ident.to_string(), trait_name);
+            if !bounds.contains(&pair) {
+                bounds.push(pair);
+            }
+        }
+    }
+    bounds
+}
+
+/// Adds a dependency arrow `"Type" ..> "Trait"` for each bound before the end of the diagram.
+fn add_bound_arrows(plantuml: &str, bounds: &[(String, String)]) -> String {
+    if bounds.is_empty() {
+        return plantuml.to_string();
+    }
+    let arrows: String = bounds
+        .iter()
+        .map(|(type_name, trait_name)| format!("\"{type_name}\" ..> \"{trait_name}\"\n"))
+        .collect();
+    match plantuml.rfind("@enduml") {
+        Some(end) => format!("{}{}\n{}", &plantuml[..end], arrows, &plantuml[end..]),
+        None => plantuml.to_string() + &arrows,
+    }
+}
+
+/// Collects the documentation of the named fields of all structs, truncated to
+/// the maximum length.
+///
+/// # Returns
+///
+/// A list of `(type name, field name, note)` triples in declaration order.
+fn collect_field_notes(items: &[Item], max_length: usize) -> Vec<(String, String, String)> {
+    let mut notes = Vec::new();
+    for item in items {
+        let Item::Struct(item_struct) = item else {
+            continue;
+        };
+        let Fields::Named(fields) = &item_struct.fields else {
+            continue;
+        };
+        for field in &fields.named {
+            let (Some(ident), Some(documentation)) =
+                (&field.ident, field_documentation(&field.attrs))
+            else {
+                continue;
+            };
+            notes.push((
+                item_struct.ident.to_string(),
+                ident.to_string(),
+                truncate(&documentation, max_length),
+            ));
+        }
+    }
+    notes
+}
+
+/// Joins the doc comment lines of a field to a single line, `None` if undocumented.
+fn field_documentation(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter_map(|attribute| match attribute.parse_meta().ok()? {
+            Meta::NameValue(name_value) if name_value.path.is_ident("doc") => {
+                match name_value.lit {
+                    Lit::Str(lit_str) => Some(lit_str.value().trim().to_string()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+/// Truncates the text to the maximum number of characters, ending it with `...`
+/// if it was cut.
+fn truncate(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max_length.saturating_sub(3)).collect();
+    format!("{}...", truncated.trim_end())
+}
+
+/// Adds a note `note right of "Type"::field : text` for each documented field
+/// before the end of the diagram.
+fn add_field_notes(plantuml: &str, notes: &[(String, String, String)]) -> String {
+    if notes.is_empty() {
+        return plantuml.to_string();
+    }
+    let lines: String = notes
+        .iter()
+        .map(|(type_name, field_name, note)| {
+            format!("note right of \"{type_name}\"::{field_name} : {note}\n")
+        })
+        .collect();
+    match plantuml.rfind("@enduml") {
+        Some(end) => format!("{}{}\n{}", &plantuml[..end], lines, &plantuml[end..]),
+        None => plantuml.to_string() + &lines,
+    }
+}
+
+/// The generic parameters of a type, which are shown in the diagram.
+struct GenericType {
+    /// The name of the type.
+    name: String,
+    /// The names of the type and const parameters, lifetimes are left out.
+    parameters: Vec<String>,
+    /// The const parameters with their type, e.g. `("N", "usize")`.
+    const_parameters: Vec<(String, String)>,
+}
+
+/// Collects the generic parameters of all structs and enums with type or const
+/// parameters.
+fn collect_generic_types(items: &[Item]) -> Vec<GenericType> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct(item_struct) => Some((&item_struct.ident, &item_struct.generics)),
+            Item::Enum(item_enum) => Some((&item_enum.ident, &item_enum.generics)),
+            _ => None,
+        })
+        .filter_map(|(ident, generics)| {
+            let generic_type = generic_type(ident.to_string(), generics);
+            (!generic_type.parameters.is_empty()).then_some(generic_type)
+        })
+        .collect()
+}
+
+/// Collects the names of the type and const parameters, distinguishing the const
+/// parameters, whose types are kept for the note.
+fn generic_type(name: String, generics: &Generics) -> GenericType {
+    let mut parameters = Vec::new();
+    let mut const_parameters = Vec::new();
+    for parameter in &generics.params {
+        match parameter {
+            GenericParam::Type(type_param) => parameters.push(type_param.ident.to_string()),
+            GenericParam::Const(const_param) => {
+                let ty = &const_param.ty;
+                parameters.push(const_param.ident.to_string());
+                const_parameters.push((const_param.ident.to_string(), quote!(#ty).to_string()));
+            }
+            GenericParam::Lifetime(_) => {}
+        }
+    }
+    GenericType {
+        name,
+        parameters,
+        const_parameters,
+    }
+}
+
+/// Adds the generic parameters behind the name in the header of the type, which
+/// PlantUML shows at the corner of the class like `Matrix<R, C>`.
+///
+/// `class "Matrix" {` becomes `class "Matrix" <R, C> {`.
+fn add_generic_parameters(plantuml: &str, generic_type: &GenericType) -> String {
+    let pattern = format!(r#"(?m)^([a-z]+ "{}") "#, regex::escape(&generic_type.name));
+    let regex = Regex::new(&pattern).unwrap();
+    regex
+        .replace_all(
+            plantuml,
+            format!("$1 <{}> ", generic_type.parameters.join(", ")).as_str(),
+        )
+        .to_string()
+}
+
+/// Adds a note `note top of "Type" : R: usize\nC: usize` listing the const
+/// generic parameters with their types for each type having some, before the
+/// end of the diagram.
+fn add_const_generics_notes(plantuml: &str, generic_types: &[GenericType]) -> String {
+    let lines: String = generic_types
+        .iter()
+        .filter(|generic_type| !generic_type.const_parameters.is_empty())
+        .map(|generic_type| {
+            let parameters: Vec<String> = generic_type
+                .const_parameters
+                .iter()
+                .map(|(name, ty)| format!("const {name}: {ty}"))
+                .collect();
+            format!(
+                "note top of \"{}\" : {}\n",
+                generic_type.name,
+                parameters.join("\\n")
+            )
+        })
+        .collect();
+    if lines.is_empty() {
+        return plantuml.to_string();
+    }
+    match plantuml.rfind("@enduml") {
+        Some(end) => format!("{}{}\n{}", &plantuml[..end], lines, &plantuml[end..]),
+        None => plantuml.to_string() + &lines,
+    }
+}
+
+/// Collects the stereotypes of all structs and enums carrying the given marker.
+///
+/// # Returns
+///
+/// A list of `(type name, stereotype)` pairs in declaration order.
+fn collect_stereotypes(items: &[Item], marker: &str) -> Vec<(String, String)> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Struct(item_struct) => Some((&item_struct.ident, &item_struct.attrs)),
+            Item::Enum(item_enum) => Some((&item_enum.ident, &item_enum.attrs)),
+            _ => None,
+        })
+        .filter_map(|(ident, attrs)| {
+            extract_stereotype(attrs, marker).map(|stereotype| (ident.to_string(), stereotype))
+        })
+        .collect()
+}
+
+/// Extracts the stereotype from the attributes of a type.
+///
+/// The stereotype is either given by an attribute named like the marker
+/// (`#[stereotype(Entity)]` or `#[stereotype = "Entity"]`) or by a doc comment
+/// line starting with the marker followed by a colon (`/// stereotype: Entity`).
+fn extract_stereotype(attrs: &[Attribute], marker: &str) -> Option<String> {
+    attrs
+        .iter()
+        .find_map(|attribute| match attribute.parse_meta().ok()? {
+            Meta::NameValue(name_value) if name_value.path.is_ident("doc") => {
+                match name_value.lit {
+                    Lit::Str(lit_str) => lit_str
+                        .value()
+                        .trim()
+                        .strip_prefix(marker)?
+                        .trim_start()
+                        .strip_prefix(':')
+                        .map(|stereotype| stereotype.trim().to_string()),
+                    _ => None,
+                }
+            }
+            Meta::NameValue(name_value) if name_value.path.is_ident(marker) => match name_value.lit
+            {
+                Lit::Str(lit_str) => Some(lit_str.value()),
+                _ => None,
+            },
+            Meta::List(list) if list.path.is_ident(marker) => {
+                list.nested.iter().find_map(|nested| match nested {
+                    NestedMeta::Meta(Meta::Path(path)) => {
+                        path.get_ident().map(|ident| ident.to_string())
+                    }
+                    NestedMeta::Lit(Lit::Str(lit_str)) => Some(lit_str.value()),
+                    _ => None,
+                })
+            }
+            _ => None,
+        })
+}
+
+/// Collects the names of the structs which are drawn with the `struct` keyword
+/// instead of `class`.
+fn collect_structs_drawn_as_struct(items: &[Item], struct_keyword: &StructKeyword) -> Vec<String> {
+    let struct_names = items.iter().filter_map(|item| match item {
+        Item::Struct(item_struct) => Some(item_struct.ident.to_string()),
+        _ => None,
+    });
+    match struct_keyword {
+        StructKeyword::Class => Vec::new(),
+        StructKeyword::Struct => struct_names.collect(),
+        StructKeyword::Auto => {
+            let types_with_methods = collect_types_with_methods(items);
+            struct_names
+                .filter(|name| !types_with_methods.contains(name))
+                .collect()
+        }
+    }
+}
+
+/// Collects the names of the types with at least one method in an inherent impl.
+fn collect_types_with_methods(items: &[Item]) -> Vec<String> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Impl(item_impl)
+                if item_impl.trait_.is_none()
+                    && item_impl
+                        .items
+                        .iter()
+                        .any(|impl_item| matches!(impl_item, ImplItem::Method(_))) =>
+            {
+                impl_type_name(item_impl)
+            }
+            _ => None,
+        })
+        .collect()
+}
 
-        ruml::render_plantuml(entities)
+/// Replaces the `class` keyword of the given type in the PlantUML string by `struct`.
+///
+/// `class "Point" {` becomes `struct "Point" {`.
+fn use_struct_keyword(plantuml: &str, type_name: &str) -> String {
+    let pattern = format!(r#"(?m)^class ("{}") "#, regex::escape(type_name));
+    let regex = Regex::new(&pattern).unwrap();
+    regex.replace_all(plantuml, "struct $1 ").to_string()
+}
+
+/// Collects the fields and the methods of inherent impls with a restricted
+/// visibility, like `pub(crate)` or `pub(super)`, as (type name, member name).
+fn collect_restricted_members(items: &[Item]) -> Vec<(String, String)> {
+    let is_restricted = |visibility: &Visibility| {
+        matches!(visibility, Visibility::Crate(_) | Visibility::Restricted(_))
+    };
+    let mut members = Vec::new();
+    for item in items {
+        match item {
+            Item::Struct(item_struct) => {
+                for field in &item_struct.fields {
+                    if let (Some(ident), true) = (&field.ident, is_restricted(&field.vis)) {
+                        members.push((item_struct.ident.to_string(), ident.to_string()));
+                    }
+                }
+            }
+            Item::Impl(item_impl) if item_impl.trait_.is_none() => {
+                let Some(type_name) = impl_type_name(item_impl) else {
+                    continue;
+                };
+                for impl_item in &item_impl.items {
+                    if let ImplItem::Method(method) = impl_item {
+                        if is_restricted(&method.vis) {
+                            members.push((type_name.clone(), method.sig.ident.to_string()));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
     }
+    members
+}
+
+/// Draws the members with a restricted visibility with the `~` symbol, which
+/// stands for the package visibility in PlantUML, whatever symbol ruml gave them.
+fn mark_restricted_members(plantuml: &str, restricted_members: &[(String, String)]) -> String {
+    let header = Regex::new(r#"^[a-z]+ "([^"]+)".*\{$"#).unwrap();
+    let mut type_name: Option<String> = None;
+    let mut output = Vec::new();
+    for line in plantuml.lines() {
+        if let Some(captures) = header.captures(line) {
+            type_name = Some(captures[1].to_string());
+        } else if line.trim() == "}" {
+            type_name = None;
+        } else if let Some(type_name) = &type_name {
+            let member = line.trim_start();
+            let name = member
+                .get(1..)
+                .unwrap_or_default()
+                .replace("{static}", "")
+                .replace("{abstract}", "");
+            let name = name
+                .trim_start()
+                .split(['(', ':'])
+                .next()
+                .unwrap_or_default();
+            let restricted = restricted_members
+                .iter()
+                .any(|(restricted_type, member_name)| {
+                    restricted_type == type_name && member_name == name.trim()
+                });
+            if restricted && member.starts_with(['+', '-', '#']) {
+                let indentation = &line[..line.len() - member.len()];
+                output.push(format!("{indentation}~{}", &member[1..]));
+                continue;
+            }
+        }
+        output.push(line.to_string());
+    }
+    output.join("\n")
+}
+
+/// The visibility groups of the members with the PlantUML visibility symbols
+/// belonging to them.
+const VISIBILITY_GROUPS: &[(&str, &[char])] = &[
+    ("Public", &['+']),
+    ("Crate", &['~', '#']),
+    ("Private", &['-']),
+];
+
+/// Groups the members in the body of each type by their visibility symbol, each
+/// group starting with a separator line like `-- Public --`. Lines without a
+/// visibility symbol stay in front, empty groups are left out.
+fn group_members_by_visibility(plantuml: &str) -> String {
+    let header = Regex::new(r#"^[a-z]+ ".*\{$"#).unwrap();
+    let mut output = Vec::new();
+    let mut body: Option<Vec<&str>> = None;
+    for line in plantuml.lines() {
+        match body.as_mut() {
+            Some(members) if line.trim() == "}" => {
+                output.extend(order_by_visibility(members));
+                output.push(line.to_string());
+                body = None;
+            }
+            Some(members) => members.push(line),
+            None => {
+                output.push(line.to_string());
+                if header.is_match(line) {
+                    body = Some(Vec::new());
+                }
+            }
+        }
+    }
+    output.join("\n")
+}
+
+/// Orders the member lines of a type body by their visibility, see
+/// [group_members_by_visibility].
+fn order_by_visibility(members: &[&str]) -> Vec<String> {
+    let symbol = |line: &str| line.trim_start().chars().next();
+    let mut ordered: Vec<String> = members
+        .iter()
+        .filter(|line| {
+            !VISIBILITY_GROUPS
+                .iter()
+                .any(|(_, symbols)| symbol(line).is_some_and(|c| symbols.contains(&c)))
+        })
+        .map(|line| line.to_string())
+        .collect();
+    for (name, symbols) in VISIBILITY_GROUPS {
+        let group: Vec<&&str> = members
+            .iter()
+            .filter(|line| symbol(line).is_some_and(|c| symbols.contains(&c)))
+            .collect();
+        if !group.is_empty() {
+            ordered.push(format!("    -- {name} --"));
+            ordered.extend(group.iter().map(|line| line.to_string()));
+        }
+    }
+    ordered
+}
+
+/// Abbreviates the types of the fields, parameters and return values in the member
+/// lines, see [truncate].
+fn abbreviate_member_types(plantuml: &str, max_length: usize) -> String {
+    let member = Regex::new(r"^\s+[-+~#] ").unwrap();
+    plantuml
+        .lines()
+        .map(|line| {
+            if member.is_match(line) {
+                abbreviate_member(line, max_length)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Abbreviates the types of a single member line like `- cache: HashMap<K, V>` or
+/// `+ insert(key: K, value: V) -> Option<V>`.
+fn abbreviate_member(line: &str, max_length: usize) -> String {
+    let (true, Some(open)) = (is_method_line(line), line.find('(')) else {
+        return abbreviate_typed_name(line, max_length);
+    };
+    let close = open + matching_parenthesis(&line[open..]).unwrap_or(line.len() - open - 1);
+    let parameters = split_top_level(&line[open + 1..close])
+        .iter()
+        .map(|parameter| abbreviate_typed_name(parameter, max_length))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let rest = &line[close + 1..];
+    let rest = match [" -> ", ": "]
+        .iter()
+        .find_map(|separator| rest.strip_prefix(separator).map(|ty| (separator, ty)))
+    {
+        Some((separator, return_type)) => {
+            format!("{separator}{}", truncate(return_type, max_length))
+        }
+        None => rest.to_string(),
+    };
+    format!("{}({parameters}){rest}", &line[..open])
+}
+
+/// Returns true if the member line is a method, whose parameter list starts before
+/// any `: `. A field can have parentheses in its type, like `size: (u32, u32)`.
+fn is_method_line(line: &str) -> bool {
+    match (line.find('('), line.find(": ")) {
+        (Some(open), Some(colon)) => open < colon,
+        (open, _) => open.is_some(),
+    }
+}
+
+/// Abbreviates the type of `name: Type`, leaving anything without a type unchanged.
+fn abbreviate_typed_name(typed_name: &str, max_length: usize) -> String {
+    match typed_name.split_once(": ") {
+        Some((name, ty)) => format!("{name}: {}", truncate(ty, max_length)),
+        None => typed_name.to_string(),
+    }
+}
+
+/// Finds the index of the parenthesis closing the one the text starts with.
+fn matching_parenthesis(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits a parameter list at the commas which aren't nested in brackets, so
+/// `a: HashMap<K, V>, b: u8` results in two parameters.
+fn split_top_level(parameters: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut previous = ' ';
+    for (index, c) in parameters.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            // the arrow of `Fn(u8) -> u8` doesn't close a bracket
+            '>' if previous == '-' => {}
+            '>' | ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(parameters[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    let last = parameters[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
+/// Collects the methods the diagram shows in the body of the given type.
+///
+/// # Returns
+///
+/// A list of `(visibility symbol, method name)` pairs in the order of the diagram,
+/// the name without its parameter list. `None` if the diagram has no such type.
+pub fn diagram_methods(plantuml: &str, type_name: &str) -> Option<Vec<(char, String)>> {
+    let header = format!("\"{type_name}\"");
+    let mut lines = plantuml.lines().skip_while(|line| {
+        !(line.ends_with('{') && line.split_whitespace().nth(1) == Some(&header))
+    });
+    lines.next()?;
+    let mut methods = Vec::new();
+    for line in lines {
+        if line.trim() == "}" {
+            break;
+        }
+        let member = line.trim_start();
+        let Some(symbol) = member.chars().next() else {
+            continue;
+        };
+        if !is_method_line(member) {
+            continue;
+        }
+        let Some((name, _)) = member[symbol.len_utf8()..].split_once('(') else {
+            continue;
+        };
+        let name = name.replace("{static}", "").replace("{abstract}", "");
+        methods.push((symbol, name.trim().to_string()));
+    }
+    Some(methods)
+}
+
+/// Extracts the block of the given type from the diagram, keeping only the member
+/// line of the method, which is highlighted in bold. The header of the type is
+/// kept as it is, with its keyword, generic parameters and stereotype.
+///
+/// # Returns
+///
+/// A diagram of the type showing only the method, without members if the diagram
+/// doesn't show the method. `None` if the diagram has no such type.
+pub fn method_diagram(plantuml: &str, type_name: &str, method_name: &str) -> Option<String> {
+    let header = format!("\"{type_name}\"");
+    let mut lines = plantuml.lines().map(str::trim).skip_while(|line| {
+        !(line.ends_with('{') && line.split_whitespace().nth(1) == Some(&header))
+    });
+    let header_line = lines.next()?;
+    let method_line = lines
+        .take_while(|line| *line != "}")
+        .filter(|line| is_method_line(line))
+        .find_map(|member| {
+            let symbol = member.chars().next()?;
+            let rest = member[symbol.len_utf8()..].trim_start();
+            let (name, _) = rest.split_once('(')?;
+            let name = name.replace("{static}", "").replace("{abstract}", "");
+            if name.trim() != method_name {
+                return None;
+            }
+            // the modifiers have to stay in front of the highlighted signature
+            let signature = rest
+                .trim_start_matches("{static}")
+                .trim_start_matches("{abstract}")
+                .trim_start();
+            let modifiers = rest[..rest.len() - signature.len()].to_string();
+            Some(format!("    {symbol} {modifiers}<b>{signature}</b>\n"))
+        })
+        .unwrap_or_default();
+    Some(format!(
+        "@startuml\n\n{header_line}\n{method_line}}}\n\n@enduml\n"
+    ))
+}
+
+/// Collects the types together with the path of their module in the order of the
+/// source, e.g. `("Order", "model::order")`. The types of the top level have an
+/// empty path.
+fn collect_type_modules(
+    items: &[Item],
+    module_path: &mut Vec<String>,
+    type_modules: &mut Vec<(String, String)>,
+) {
+    for item in items {
+        let name = match item {
+            Item::Struct(item_struct) => item_struct.ident.to_string(),
+            Item::Enum(item_enum) => item_enum.ident.to_string(),
+            Item::Trait(item_trait) => item_trait.ident.to_string(),
+            Item::Mod(item_mod) => {
+                if let Some((_, mod_items)) = &item_mod.content {
+                    module_path.push(item_mod.ident.to_string());
+                    collect_type_modules(mod_items, module_path, type_modules);
+                    module_path.pop();
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        type_modules.push((name, module_path.join("::")));
+    }
+}
+
+/// Moves the blocks of the types declared in inline modules into a package per
+/// module, which is placed where the first of the moved blocks was. The types of
+/// the top level and the relationships stay outside of the packages.
+///
+/// The blocks are in the order of the source, so the n-th block of a type name
+/// belongs to the module of the n-th type of that name, e.g. of two `Item` types
+/// of different modules.
+fn group_into_packages(plantuml: &str, type_modules: &[(String, String)]) -> String {
+    if type_modules.iter().all(|(_, module)| module.is_empty()) {
+        return plantuml.to_string();
+    }
+    let mut assigned = vec![false; type_modules.len()];
+    let header = Regex::new(r#"^[a-z]+ "([^"]+)""#).unwrap();
+    let mut output: Vec<String> = Vec::new();
+    let mut packages: Vec<(&str, Vec<String>)> = Vec::new();
+    let mut packages_position = None;
+    let mut current: Option<(&str, Vec<String>)> = None;
+    // the empty line separating a moved block from the next one is dropped as well
+    let mut skip_empty_line = false;
+    for line in plantuml.lines() {
+        if std::mem::take(&mut skip_empty_line) && line.is_empty() {
+            continue;
+        }
+        if let Some((module, block)) = current.as_mut() {
+            block.push(line.to_string());
+            if line.trim() == "}" {
+                add_to_package(&mut packages, module, std::mem::take(block));
+                current = None;
+                skip_empty_line = true;
+            }
+            continue;
+        }
+        let module = header.captures(line).and_then(|captures| {
+            let index = (0..type_modules.len())
+                .find(|&index| !assigned[index] && type_modules[index].0 == captures[1])?;
+            assigned[index] = true;
+            let module = type_modules[index].1.as_str();
+            (!module.is_empty()).then_some(module)
+        });
+        match module {
+            Some(module) => {
+                packages_position.get_or_insert(output.len());
+                if line.trim_end().ends_with('{') {
+                    current = Some((module, vec![line.to_string()]));
+                } else {
+                    add_to_package(&mut packages, module, vec![line.to_string()]);
+                    skip_empty_line = true;
+                }
+            }
+            None => output.push(line.to_string()),
+        }
+    }
+    let Some(position) = packages_position else {
+        return plantuml.to_string();
+    };
+    let blocks: Vec<String> = packages
+        .iter()
+        .map(|(module, lines)| {
+            let indented: String = lines
+                .iter()
+                .map(|line| match line.as_str() {
+                    "" => String::from("\n"),
+                    line => format!("    {line}\n"),
+                })
+                .collect();
+            format!("package \"{module}\" {{\n{indented}}}")
+        })
+        .collect();
+    output.insert(position, format!("{}\n", blocks.join("\n")));
+    output.join("\n")
+}
+
+/// Appends the lines of a type block to the package of the module, see [group_into_packages].
+fn add_to_package<'a>(
+    packages: &mut Vec<(&'a str, Vec<String>)>,
+    module: &'a str,
+    block: Vec<String>,
+) {
+    match packages.iter_mut().find(|(name, _)| *name == module) {
+        Some((_, lines)) => lines.extend(block),
+        None => packages.push((module, block)),
+    }
+}
+
+/// Wraps the content between `@startuml` and `@enduml` in a namespace block, so
+/// the types don't clash with those of other diagrams combined in one document.
+fn wrap_in_namespace(plantuml: &str, namespace: &str) -> String {
+    let content = plantuml
+        .trim()
+        .trim_start_matches("@startuml")
+        .trim_end_matches("@enduml")
+        .trim_matches('\n');
+    // an empty diagram stays empty
+    if content.trim().is_empty() {
+        return plantuml.to_string();
+    }
+    let indented: Vec<String> = content
+        .lines()
+        .map(|line| match line {
+            "" => String::new(),
+            line => format!("    {line}"),
+        })
+        .collect();
+    format!(
+        "@startuml\n\nnamespace {namespace} {{\n{}\n}}\n\n@enduml",
+        indented.join("\n")
+    )
+}
+
+/// Adds a legend with a table of the symbols and their meaning before the end of
+/// the diagram. Without entries the diagram stays unchanged.
+fn add_legend(plantuml: &str, entries: &[(String, String)]) -> String {
+    if entries.is_empty() {
+        return plantuml.to_string();
+    }
+    let rows: String = entries
+        .iter()
+        .map(|(symbol, meaning)| format!("| {symbol} | {meaning} |\n"))
+        .collect();
+    let legend = format!("legend right\n|= Symbol |= Meaning |\n{rows}endlegend\n");
+    match plantuml.rfind("@enduml") {
+        Some(end) => format!("{}{}\n{}", &plantuml[..end], legend, &plantuml[end..]),
+        None => plantuml.to_string() + &legend,
+    }
+}
+
+/// Inserts the preamble, like `!define` or `!pragma` directives, right after the
+/// `@startuml` line of the diagram, before anything else of it.
+pub fn insert_preamble(plantuml: &str, preamble: &str) -> String {
+    match plantuml.split_once('\n') {
+        Some((start, rest)) => format!("{start}\n{}\n{rest}", preamble.trim_end()),
+        None => plantuml.to_string(),
+    }
+}
+
+/// Adds a stereotype to the header of the given type in the PlantUML string.
+///
+/// `class "Order" {` becomes `class "Order" <<Entity>> {`.
+fn add_stereotype(plantuml: &str, type_name: &str, stereotype: &str) -> String {
+    let pattern = format!(r#"(?m)^([a-z]+ "{}") \{{"#, regex::escape(type_name));
+    let regex = Regex::new(&pattern).unwrap();
+    regex
+        .replace_all(plantuml, format!("$1 <<{stereotype}>> {{").as_str())
+        .to_string()
 }
 
 #[cfg(test)]
@@ -55,6 +1065,7 @@ mod tests {
 
         let parser = PlantumlParser {
             raw_rust_code: rust_code,
+            ..Default::default()
         };
         let actual_puml = parser.parse_code_to_string();
 
@@ -69,9 +1080,359 @@ mod tests {
 
         let parser = PlantumlParser {
             raw_rust_code: rust_code,
+            ..Default::default()
         };
         let actual_puml = parser.parse_code_to_string();
 
         assert_eq!(String::from(expected_puml), actual_puml,);
     }
+
+    #[test]
+    fn test_parse_code_to_string_skips_items_local_to_functions() {
+        let rust_code = String::from(
+            r#"
+            pub struct Parser { pub input: String }
+            impl Parser {
+                pub fn parse(&self) {
+                    struct Token { pub text: String }
+                }
+            }
+            fn helper() {
+                pub struct State { pub position: usize }
+            }
+            "#,
+        );
+        let parser = PlantumlParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+
+        let actual_puml = parser.parse_code_to_string();
+
+        assert!(actual_puml.contains("class \"Parser\""));
+        assert!(!actual_puml.contains("Token"));
+        assert!(!actual_puml.contains("State"));
+    }
+
+    #[test]
+    fn test_parse_code_to_string_with_automatic_struct_keyword() {
+        let rust_code = String::from(
+            r#"
+            struct Point { x: i32 }
+            struct Counter { count: u32 }
+            impl Counter {
+                fn increment(&mut self) {}
+            }
+            "#,
+        );
+
+        let parser = PlantumlParser {
+            raw_rust_code: rust_code,
+            struct_keyword: StructKeyword::Auto,
+            ..Default::default()
+        };
+        let actual_puml = parser.parse_code_to_string();
+
+        assert!(actual_puml.contains("struct \"Point\" {"));
+        assert!(actual_puml.contains("class \"Counter\" {"));
+    }
+
+    #[test]
+    fn test_diagram_methods() {
+        let plantuml = "@startuml\n\nclass \"Person\" {\n    - id: u32\n    + new(name: String)\n    - {static} count()\n}\n\nclass \"Order\" {\n    + cancel()\n}\n\n@enduml";
+
+        assert_eq!(
+            diagram_methods(plantuml, "Person"),
+            Some(vec![
+                ('+', String::from("new")),
+                ('-', String::from("count"))
+            ])
+        );
+        assert_eq!(diagram_methods(plantuml, "Customer"), None);
+    }
+
+    #[test]
+    fn test_parse_code_to_string_with_const_generics() {
+        let rust_code =
+            String::from("struct Matrix<const R: usize, const C: usize> { values: [[f64; C]; R] }");
+
+        let parser = PlantumlParser {
+            raw_rust_code: rust_code,
+            const_generics_note: true,
+            ..Default::default()
+        };
+        let actual_puml = parser.parse_code_to_string();
+
+        assert!(actual_puml.contains("class \"Matrix\" <R, C> {\n"));
+        assert!(actual_puml
+            .contains("note top of \"Matrix\" : const R: usize\\nconst C: usize\n\n@enduml"));
+    }
+
+    #[test]
+    fn test_group_into_packages() {
+        let plantuml = "@startuml\n\nclass \"Shop\" {\n    - orders: Vec<Order>\n}\n\nclass \"Order\" {\n    - amount: u64\n}\n\nenum \"State\" {\n    + Open\n}\n\n\"Shop\" --> \"Order\"\n\n@enduml";
+        let type_modules = vec![
+            (String::from("Shop"), String::new()),
+            (String::from("Order"), String::from("model")),
+            (String::from("State"), String::from("model")),
+        ];
+
+        assert_eq!(
+            group_into_packages(plantuml, &type_modules),
+            "@startuml\n\nclass \"Shop\" {\n    - orders: Vec<Order>\n}\n\npackage \"model\" {\n    class \"Order\" {\n        - amount: u64\n    }\n    enum \"State\" {\n        + Open\n    }\n}\n\n\"Shop\" --> \"Order\"\n\n@enduml"
+        );
+        assert_eq!(group_into_packages(plantuml, &[]), plantuml);
+    }
+
+    #[test]
+    fn test_collect_type_modules() {
+        let file = syn::parse_file(
+            "struct Shop {} mod model { pub struct Order {} pub mod state { pub enum State {} } }",
+        )
+        .unwrap();
+        let mut type_modules = Vec::new();
+        collect_type_modules(&file.items, &mut Vec::new(), &mut type_modules);
+
+        assert_eq!(
+            type_modules,
+            vec![
+                (String::from("Shop"), String::new()),
+                (String::from("Order"), String::from("model")),
+                (String::from("State"), String::from("model::state")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_code_to_string_with_package_by_module() {
+        let parser = PlantumlParser {
+            raw_rust_code: String::from(
+                "struct Shop { orders: Vec<Order> }\n\
+                mod model { pub struct Order { amount: u64 } }\n\
+                mod stock { pub struct Order { count: u32 } }",
+            ),
+            package_by_module: true,
+            ..Default::default()
+        };
+
+        let plantuml = parser.parse_code_to_string();
+
+        assert!(plantuml.starts_with("@startuml\n\nclass \"Shop\" {\n"));
+        assert!(plantuml.contains(
+            "package \"model\" {\n    class \"Order\" {\n        - amount: u64\n    }\n}\n"
+        ));
+        assert!(plantuml.contains(
+            "package \"stock\" {\n    class \"Order\" {\n        - count: u32\n    }\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_parse_code_to_string_wrapped_in_namespace() {
+        let parser = PlantumlParser {
+            raw_rust_code: String::from("struct Person { id: u32 }"),
+            namespace: Some(String::from("my_crate")),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parser.parse_code_to_string(),
+            "@startuml\n\nnamespace my_crate {\n    class \"Person\" {\n        - id: u32\n    }\n}\n\n@enduml"
+        );
+    }
+
+    #[test]
+    fn test_parse_code_to_string_with_legend() {
+        let parser = PlantumlParser {
+            raw_rust_code: String::from(
+                "/// stereotype: Entity\npub struct Person { pub id: u32, name: String }",
+            ),
+            stereotype_from: Some(String::from("stereotype")),
+            struct_keyword: StructKeyword::Auto,
+            legend: true,
+            ..Default::default()
+        };
+
+        let plantuml = parser.parse_code_to_string();
+
+        assert!(plantuml.ends_with(
+            "legend right\n|= Symbol |= Meaning |\n| + | public |\n| - | private |\n\
+            | struct | struct without methods |\n| «Entity» | stereotype given by 'stereotype' |\n\
+            endlegend\n\n@enduml"
+        ));
+    }
+
+    #[test]
+    fn test_insert_preamble() {
+        let plantuml = "@startuml\n\nclass \"Person\" {\n}\n\n@enduml";
+        let preamble = "!pragma layout smetana\n!define ENTITY class\n";
+
+        assert_eq!(
+            insert_preamble(plantuml, preamble),
+            "@startuml\n!pragma layout smetana\n!define ENTITY class\n\nclass \"Person\" {\n}\n\n@enduml"
+        );
+    }
+
+    #[test]
+    fn test_group_members_by_visibility() {
+        let plantuml = "@startuml\n\nclass \"Person\" {\n    - id: u32\n    + name: String\n    ~ cache: Cache\n    + new()\n}\n\n@enduml";
+        let expected_puml = "@startuml\n\nclass \"Person\" {\n    -- Public --\n    + name: String\n    + new()\n    -- Crate --\n    ~ cache: Cache\n    -- Private --\n    - id: u32\n}\n\n@enduml";
+
+        assert_eq!(group_members_by_visibility(plantuml), expected_puml);
+    }
+
+    #[test]
+    fn test_parse_code_to_string_groups_crate_visible_members() {
+        let rust_code = String::from(
+            r#"
+            pub struct Person {
+                pub name: String,
+                pub(crate) cache: Vec<u8>,
+                id: u32,
+            }
+            impl Person {
+                pub fn new() -> Self { todo!() }
+                pub(crate) fn refresh(&mut self) {}
+                pub(super) fn reset(&mut self) {}
+            }
+            "#,
+        );
+        let parser = PlantumlParser {
+            raw_rust_code: rust_code,
+            group_by_visibility: true,
+            ..Default::default()
+        };
+
+        let actual_puml = parser.parse_code_to_string();
+
+        let crate_group = actual_puml
+            .split("-- Crate --")
+            .nth(1)
+            .and_then(|rest| rest.split("-- Private --").next())
+            .expect("No Crate group");
+        let crate_members: Vec<&str> = crate_group.lines().map(str::trim).collect();
+        assert!(crate_members
+            .iter()
+            .any(|line| line.starts_with("~ cache:")));
+        assert!(crate_members
+            .iter()
+            .any(|line| line.starts_with("~ refresh(")));
+        assert!(crate_members
+            .iter()
+            .any(|line| line.starts_with("~ reset(")));
+        assert!(!crate_group.contains("name"));
+        assert!(!crate_group.contains("id"));
+    }
+
+    #[test]
+    fn test_mark_restricted_members() {
+        let plantuml = "@startuml\n\nclass \"Person\" {\n    - cache: Cache\n    - id: u32\n    + {static} refresh()\n}\n\nclass \"Order\" {\n    - cache: Cache\n}\n\n@enduml";
+        let restricted_members = [
+            (String::from("Person"), String::from("cache")),
+            (String::from("Person"), String::from("refresh")),
+        ];
+        let expected_puml = "@startuml\n\nclass \"Person\" {\n    ~ cache: Cache\n    - id: u32\n    ~ {static} refresh()\n}\n\nclass \"Order\" {\n    - cache: Cache\n}\n\n@enduml";
+
+        assert_eq!(
+            mark_restricted_members(plantuml, &restricted_members),
+            expected_puml
+        );
+    }
+
+    #[test]
+    fn test_parse_code_to_string_with_field_notes() {
+        let rust_code = String::from(
+            r#"
+            struct Person {
+                /// The full name
+                /// of the person.
+                name: String,
+                /// The postal address the invoices are sent to.
+                address: String,
+                age: u8,
+            }
+            "#,
+        );
+
+        let parser = PlantumlParser {
+            raw_rust_code: rust_code,
+            field_notes: Some(20),
+            ..Default::default()
+        };
+        let actual_puml = parser.parse_code_to_string();
+
+        assert!(actual_puml.ends_with(
+            "note right of \"Person\"::name : The full name of...\n\
+            note right of \"Person\"::address : The postal addres...\n\n@enduml"
+        ));
+        assert!(!actual_puml.contains("::age"));
+    }
+
+    #[test]
+    fn test_abbreviate_member_types() {
+        let plantuml = "class \"Cache\" {\n    - entries: HashMap<String, Vec<Result<T, E>>>\n    - filter: Box<dyn Fn(u8) -> bool>\n    + get(&self, key: &str, filter: Box<dyn Fn(u8) -> bool>) -> Option<Vec<Result<T, E>>>\n}";
+        let expected_puml = "class \"Cache\" {\n    - entries: HashMap<String, V...\n    - filter: Box<dyn Fn(u8) ->...\n    + get(&self, key: &str, filter: Box<dyn Fn(u8) ->...) -> Option<Vec<Result...\n}";
+
+        assert_eq!(abbreviate_member_types(plantuml, 20), expected_puml);
+    }
+
+    #[test]
+    fn test_parse_code_to_string_with_stereotype_from_doc_marker() {
+        let rust_code = String::from(
+            r#"
+            #[doc = "stereotype: Entity"]
+            struct Order { id: u32 }
+            struct Customer { id: u32 }
+            "#,
+        );
+
+        let parser = PlantumlParser {
+            raw_rust_code: rust_code,
+            stereotype_from: Some(String::from("stereotype")),
+            ..Default::default()
+        };
+        let actual_puml = parser.parse_code_to_string();
+
+        assert!(actual_puml.contains("class \"Order\" <<Entity>> {"));
+        assert!(actual_puml.contains("class \"Customer\" {"));
+    }
+
+    #[test]
+    fn test_extract_stereotype_from_attribute() {
+        let item_struct: syn::ItemStruct =
+            syn::parse_str("#[stereotype(ValueObject)] struct Money { amount: u64 }").unwrap();
+
+        let stereotype = extract_stereotype(&item_struct.attrs, "stereotype");
+
+        assert_eq!(stereotype, Some(String::from("ValueObject")));
+    }
+
+    #[test]
+    fn test_parse_code_to_string_with_bound_arrows() {
+        let rust_code = String::from(
+            r#"
+            struct Cache<K: Hash + Storage, V: ?Sized> where V: Clone + Storage {
+                entries: Vec<K>,
+            }
+            "#,
+        );
+
+        let parser = PlantumlParser {
+            raw_rust_code: rust_code.clone(),
+            bound_arrows: true,
+            ..Default::default()
+        };
+        let std_excluded_parser = PlantumlParser {
+            raw_rust_code: rust_code,
+            bound_arrows: true,
+            exclude_std_bounds: true,
+            ..Default::default()
+        };
+        let plantuml = parser.parse_code_to_string();
+        let std_excluded_plantuml = std_excluded_parser.parse_code_to_string();
+
+        assert!(plantuml.ends_with(
+            "\"Cache\" ..> \"Hash\"\n\"Cache\" ..> \"Storage\"\n\"Cache\" ..> \"Clone\"\n\n@enduml"
+        ));
+        assert!(std_excluded_plantuml.ends_with("\n\"Cache\" ..> \"Storage\"\n\n@enduml"));
+    }
 }
@@ -1,69 +1,98 @@
 //! A module for parsing Rust code documentation and generating Markdown documentation.
 
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use regex::Regex;
 use syn::__private::quote::quote;
-use syn::{Fields, FieldsNamed, ImplItem, Item, Meta};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::token::Comma;
+use syn::visit::Visit;
+use syn::{
+    Attribute, ExprStruct, Fields, FieldsNamed, FnArg, ForeignItem, GenericArgument, GenericParam,
+    Generics, Ident, ImplItem, ImplItemMethod, Item, ItemForeignMod, ItemImpl, ItemTrait, ItemUse,
+    Member, Meta, MetaList, NestedMeta, Pat, Path, PathArguments, ReturnType, Signature, TraitItem,
+    Type, TypeParamBound, UseTree, Visibility, WherePredicate,
+};
 
-use crate::model::class_object::{Class, Method};
+use crate::diagnostics::Diagnostics;
+use crate::model::class_object::{Class, ForeignMod, Method, Trait, TraitImpl};
+use crate::parser::{
+    impl_type_name, module_items, remove_function_local_items, remove_test_modules,
+};
 
 /// RustDocParser struct used for parsing Rust code documentation.
 #[derive(Default)]
 pub struct RustDocParser {
     pub(crate) raw_rust_code: String,
+    /// Whether the default values of the fields are added to their documentation.
+    pub(crate) show_defaults: bool,
+    /// Whether the `Result` or `Option` return type of methods without own
+    /// returns section is documented.
+    pub(crate) document_returns: bool,
+    /// Whether private fields, methods and constants are documented as well.
+    /// Otherwise only public items are documented, like rustdoc does.
+    pub(crate) document_private_items: bool,
+    /// Whether the source code of each method is added to its documentation.
+    pub(crate) embed_source: bool,
+    /// The attributes which exclude the struct, field, method or constant
+    /// carrying them from the documentation, e.g. `internal` for `#[internal]`.
+    /// A `#[doc(alias = "...")]` with one of the names excludes the item as well.
+    pub(crate) exclude_attributes: Vec<String>,
+    /// Whether types are rendered with their full path as written in the source.
+    /// Otherwise the paths of the standard library types are left out, so
+    /// `std::collections::HashMap<K, V>` is rendered as `HashMap<K, V>`.
+    pub(crate) canonical_paths: bool,
+    /// Whether all attributes of the struct except the doc comments are listed
+    /// verbatim at the top of its documentation.
+    pub(crate) show_attributes: bool,
+    /// Whether the methods of the inherent impl blocks are ordered like the
+    /// declarations of the types they belong to, keeping the methods of each
+    /// impl block together. Otherwise they are in the order of the impl blocks.
+    pub(crate) merge_impls_into_declaration_order: bool,
+    /// Whether the impl blocks marked `#[automatically_derived]`, as expanded
+    /// from a `#[derive]`, are documented, labeled as derived. Otherwise they are
+    /// left out, as they aren't written by hand.
+    pub(crate) document_derived_impls: bool,
+    /// Whether the items of `#[cfg(test)]` modules are documented.
+    pub(crate) include_tests: bool,
 }
 
 impl RustDocParser {
-    /// Parses the given Rust code documentation and returns it in Markdown format.
-    ///
-    /// This function primarily focuses on extracting documentation of structs
-    /// and their named fields.
-    ///
-    /// # Examples
-    ///
-    /// ```rust
-    /// # use crate_name::RustDocParser;
-    /// let rust_code = r#"
-    ///     /// A sample struct.
-    ///     struct Sample {
-    ///         /// A sample field.
-    ///         field: String,
-    ///     }
-    /// "#;
-    /// let parser = RustDocParser { raw_rust_code: rust_code.to_string() };
-    /// let markdown = parser.parse_code_doc_to_markdown_string();
-    /// assert!(markdown.contains("A sample struct."));
-    /// ```
-    ///
-    /// # Returns
-    ///
-    /// A string containing the code documentation formatted as Markdown.
-    pub fn parse_code_doc_to_markdown_string(&self) -> String {
-        let mut markdown = String::new();
-
-        let result = self.parse_code_doc();
-        markdown.push_str(&format!("## {}\n\n", result.name));
-        markdown.push_str(&result.documentation.to_string());
-        markdown
-    }
-
-    /// Parses the Rust code documentation and returns a representation in the form of a `Class` object.
-    ///
-    /// This function will extract the name and documentation of structs, and for each named field
-    /// inside a struct, it will treat it as a "method" with its respective documentation.
-    ///
-    /// # Returns
-    ///
-    /// A `Class` instance representing the parsed Rust documentation.
-    pub fn parse_code_doc(&self) -> Class {
-        let parsed_file = syn::parse_file(&self.raw_rust_code).unwrap();
+    /// Parses the Rust code documentation to a [Class] and
+    /// records a warning for every item which can't be documented, e.g. macro
+    /// invocations in item position, whose expansion can't be introspected.
+    pub fn parse_code_doc_with_diagnostics(&self, diagnostics: &mut Diagnostics) -> Class {
+        let mut parsed_file = syn::parse_file(&self.raw_rust_code).unwrap();
+        if !self.include_tests {
+            remove_test_modules(&mut parsed_file.items);
+        }
+        remove_function_local_items(&mut parsed_file.items);
 
         let mut struct_name = String::new();
+        let mut struct_ident = String::new();
         let mut struct_documentation = String::new();
-        let mut methods_vector = Vec::new();
+        let mut impl_blocks: Vec<(Option<String>, Vec<Method>)> = Vec::new();
+        let mut declared_types = Vec::new();
         let mut fields_vector = Vec::new();
-        for item in parsed_file.items {
+        let mut constants_vector = Vec::new();
+        let mut trait_impls = Vec::new();
+        let mut traits = Vec::new();
+        let mut reexports = Vec::new();
+        let mut foreign_mods = Vec::new();
+        let mut derives_default = false;
+        let mut default_impls = Vec::new();
+        let mut serde_defaults = Vec::new();
+        for item in module_items(parsed_file.items) {
             match item {
-                Item::Struct(item_struct) => {
+                Item::Struct(item_struct) if !self.is_excluded(&item_struct.attrs) => {
                     struct_name.push_str(&format!("{}", item_struct.ident));
+                    struct_name.push_str(&generic_parameters(&item_struct.generics));
+                    struct_ident.push_str(&item_struct.ident.to_string());
+                    declared_types.push(item_struct.ident.to_string());
+                    derives_default = has_derive_default(&item_struct.attrs);
+                    if self.show_attributes {
+                        struct_documentation.push_str(&render_attributes(&item_struct.attrs));
+                    }
                     for attribute in item_struct.attrs {
                         let meta = attribute.parse_meta().unwrap();
                         add_name_value_to_documentation(&mut struct_documentation, meta);
@@ -72,107 +101,1115 @@ impl RustDocParser {
 
                     // Collect information about fields and their documentation
                     if let Fields::Named(fields) = &item_struct.fields {
-                        fields_vector = collect_fields(fields.clone());
+                        fields_vector = self.collect_fields(fields);
+                        serde_defaults = collect_serde_defaults(fields);
+                    }
+                }
+                Item::Impl(item_impl)
+                    if self.document_derived_impls
+                        || !is_automatically_derived(&item_impl.attrs) =>
+                {
+                    if is_default_impl(&item_impl) {
+                        default_impls.push(collect_default_expressions(&item_impl));
+                    }
+                    if let Some((_, trait_path, _)) = &item_impl.trait_ {
+                        let derived = is_automatically_derived(&item_impl.attrs);
+                        let mut trait_impl = self.collect_trait_impl(
+                            path_to_string(trait_path),
+                            item_impl,
+                            diagnostics,
+                        );
+                        if derived {
+                            trait_impl
+                                .documentation
+                                .insert_str(0, "_Automatically derived_\n");
+                        }
+                        trait_impls.push(trait_impl);
+                    } else {
+                        let type_name = impl_type_name(&item_impl);
+                        let collected_methods: Vec<Method> =
+                            self.collect_methods(item_impl.items, true, diagnostics);
+                        impl_blocks.push((type_name, collected_methods));
                     }
                 }
-                Item::Impl(item_impl) => {
-                    if item_impl.trait_.is_none() {
-                        let collected_methods: Vec<Method> = collect_methods(item_impl.items);
-                        methods_vector.extend(collected_methods);
+                Item::Const(item_const)
+                    if (self.document_private_items || is_public(&item_const.vis))
+                        && !self.is_excluded(&item_const.attrs) =>
+                {
+                    let (ty, expr) = (&item_const.ty, &item_const.expr);
+                    constants_vector.push(collect_constant(
+                        &item_const.ident,
+                        Some(&item_const.vis),
+                        self.type_to_string(ty),
+                        tokens_to_string(quote!(#expr)),
+                        &item_const.attrs,
+                    ));
+                }
+                Item::Static(item_static)
+                    if (self.document_private_items || is_public(&item_static.vis))
+                        && !self.is_excluded(&item_static.attrs) =>
+                {
+                    let (ty, expr) = (&item_static.ty, &item_static.expr);
+                    constants_vector.push(collect_constant(
+                        &item_static.ident,
+                        Some(&item_static.vis),
+                        self.type_to_string(ty),
+                        tokens_to_string(quote!(#expr)),
+                        &item_static.attrs,
+                    ));
+                }
+                Item::Trait(item_trait)
+                    if (self.document_private_items || is_public(&item_trait.vis))
+                        && !self.is_excluded(&item_trait.attrs) =>
+                {
+                    traits.push(self.collect_trait(&item_trait));
+                }
+                // a private `use` only imports, it doesn't re-export
+                Item::Use(item_use)
+                    if !matches!(item_use.vis, Visibility::Inherited)
+                        && (self.document_private_items || is_public(&item_use.vis))
+                        && !self.is_excluded(&item_use.attrs) =>
+                {
+                    reexports.extend(collect_reexports(&item_use));
+                }
+                Item::ForeignMod(item_foreign_mod) => {
+                    let foreign_mod = self.collect_foreign_mod(&item_foreign_mod);
+                    if !foreign_mod.items.is_empty() {
+                        foreign_mods.push(foreign_mod);
                     }
                 }
+                Item::Macro(item_macro) if item_macro.ident.is_none() => {
+                    diagnostics.warn(format!(
+                        "Skipped macro invocation '{}!', its items can't be documented",
+                        path_to_string(&item_macro.mac.path)
+                    ));
+                }
+                Item::Verbatim(tokens) => {
+                    diagnostics.warn(format!(
+                        "Skipped item which could not be parsed: '{}'",
+                        tokens
+                    ));
+                }
                 _ => {}
             }
         }
-        Class {
+
+        if self.merge_impls_into_declaration_order {
+            // the sort is stable, so the impl blocks of one type keep their order
+            impl_blocks.sort_by_key(|(type_name, _)| {
+                declared_types
+                    .iter()
+                    .position(|declared| Some(declared) == type_name.as_ref())
+                    .unwrap_or(usize::MAX)
+            });
+        }
+        let methods_vector: Vec<Method> = impl_blocks
+            .into_iter()
+            .flat_map(|(_, methods)| methods)
+            .collect();
+
+        if self.show_defaults {
+            let mut field_defaults = Vec::new();
+            if derives_default {
+                field_defaults.extend(fields_vector.iter().map(|field| {
+                    (
+                        field.name.clone(),
+                        String::from("`Default::default()` (derived)"),
+                    )
+                }));
+            }
+            for (type_name, defaults) in default_impls {
+                if type_name == struct_ident {
+                    field_defaults.extend(defaults);
+                }
+            }
+            field_defaults.extend(serde_defaults);
+            add_defaults_to_documentation(&mut fields_vector, &field_defaults);
+        }
+
+        let mut class = Class {
             plantuml: String::new(),
             name: struct_name,
             documentation: struct_documentation,
             fields: fields_vector,
             methods: methods_vector,
+            constants: constants_vector,
+            trait_impls,
+            traits,
+            reexports,
+            foreign_mods,
+        };
+        class.for_each_documentation(|documentation| {
+            *documentation = gather_reference_definitions(documentation);
+        });
+        class
+    }
+
+    /// Collects the foreign functions with their signatures and the foreign
+    /// statics of an `extern` block, together with its ABI.
+    fn collect_foreign_mod(&self, item_foreign_mod: &ItemForeignMod) -> ForeignMod {
+        let abi = item_foreign_mod
+            .abi
+            .name
+            .as_ref()
+            .map(|name| name.value())
+            .unwrap_or_else(|| String::from("C"));
+        let mut items = Vec::new();
+        for foreign_item in &item_foreign_mod.items {
+            let (ident, visibility, attributes, name) = match foreign_item {
+                ForeignItem::Fn(foreign_fn) => {
+                    let signature = &foreign_fn.sig;
+                    let mut name = format!(
+                        "{}({})",
+                        signature.ident,
+                        self.format_parameters(&signature.inputs)
+                    );
+                    if let ReturnType::Type(_, return_type) = &signature.output {
+                        name.push_str(&format!(" -> {}", self.type_to_string(return_type)));
+                    }
+                    (&signature.ident, &foreign_fn.vis, &foreign_fn.attrs, name)
+                }
+                ForeignItem::Static(foreign_static) => {
+                    let ty = &foreign_static.ty;
+                    let mutability = if foreign_static.mutability.is_some() {
+                        "mut "
+                    } else {
+                        ""
+                    };
+                    let name = format!(
+                        "static {mutability}{}: {}",
+                        foreign_static.ident,
+                        self.type_to_string(ty)
+                    );
+                    (
+                        &foreign_static.ident,
+                        &foreign_static.vis,
+                        &foreign_static.attrs,
+                        name,
+                    )
+                }
+                _ => continue,
+            };
+            if !self.document_private_items && !is_public(visibility)
+                || self.is_excluded(attributes)
+            {
+                continue;
+            }
+            let mut documentation = String::new();
+            for attribute in attributes {
+                let meta = attribute.parse_meta().unwrap();
+                add_name_value_to_documentation(&mut documentation, meta);
+            }
+            items.push(Method {
+                name,
+                documentation,
+                line: Some(ident.span().start().line),
+                visibility: Some(visibility_to_string(visibility)),
+                optional_type: None,
+                builder: false,
+                provided: false,
+            });
         }
+        ForeignMod { abi, items }
     }
-}
-fn collect_fields(fields: FieldsNamed) -> Vec<Method> {
-    let mut fields_vector = Vec::new();
-    for field in &fields.named {
-        let method_name = field.ident.as_ref().unwrap().to_string();
-        let mut fields_documentation = String::new();
 
-        for attribute in &field.attrs {
+    /// Collects the methods declared by a trait and whether it is object-safe.
+    ///
+    /// Methods bound by `where Self: Sized` are noted in their documentation, as
+    /// they can't be called on a trait object. The trait is considered object-safe
+    /// unless it requires `Self: Sized` itself or any other method is generic, has
+    /// no receiver or returns `Self` by value.
+    fn collect_trait(&self, item_trait: &ItemTrait) -> Trait {
+        let mut documentation = String::new();
+        for attribute in &item_trait.attrs {
+            let meta = attribute.parse_meta().unwrap();
+            add_name_value_to_documentation(&mut documentation, meta);
+        }
+        let mut object_safe = !item_trait.supertraits.iter().any(is_sized_bound);
+        let mut methods = Vec::new();
+        for item in &item_trait.items {
+            let TraitItem::Method(trait_method) = item else {
+                continue;
+            };
+            if self.is_excluded(&trait_method.attrs) {
+                continue;
+            }
+            let signature = &trait_method.sig;
+            let mut method_documentation = String::new();
+            for attribute in &trait_method.attrs {
+                let meta = attribute.parse_meta().unwrap();
+                add_name_value_to_documentation(&mut method_documentation, meta);
+            }
+            if requires_sized_self(signature) {
+                method_documentation.push_str(
+                    "\nRequires `Self: Sized`, so it can't be called on a trait object.\n",
+                );
+            } else if !is_dispatchable(signature) {
+                object_safe = false;
+            }
+            methods.push(Method {
+                name: format!(
+                    "{}({})",
+                    signature.ident,
+                    self.format_parameters(&signature.inputs)
+                ),
+                documentation: method_documentation,
+                line: Some(signature.ident.span().start().line),
+                visibility: None,
+                optional_type: None,
+                builder: false,
+                provided: trait_method.default.is_some(),
+            });
+        }
+        Trait {
+            name: item_trait.ident.to_string(),
+            documentation,
+            object_safe,
+            methods,
+        }
+    }
+
+    /// Collects the associated constants and methods of a trait impl. Their
+    /// visibility is inherited from the trait, so they are collected regardless
+    /// of `document_private_items`.
+    fn collect_trait_impl(
+        &self,
+        name: String,
+        item_impl: ItemImpl,
+        diagnostics: &mut Diagnostics,
+    ) -> TraitImpl {
+        let mut documentation = String::new();
+        for attribute in &item_impl.attrs {
             let meta = attribute.parse_meta().unwrap();
-            add_name_value_to_documentation(&mut fields_documentation, meta);
+            add_name_value_to_documentation(&mut documentation, meta);
+        }
+        let constants = item_impl
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                ImplItem::Const(impl_item_const) if !self.is_excluded(&impl_item_const.attrs) => {
+                    let (ty, expr) = (&impl_item_const.ty, &impl_item_const.expr);
+                    Some(collect_constant(
+                        &impl_item_const.ident,
+                        None,
+                        self.type_to_string(ty),
+                        tokens_to_string(quote!(#expr)),
+                        &impl_item_const.attrs,
+                    ))
+                }
+                _ => None,
+            })
+            .collect();
+        let methods = self.collect_methods(item_impl.items, false, diagnostics);
+        TraitImpl {
+            name,
+            documentation,
+            constants,
+            methods,
         }
+    }
 
-        let method = Method {
-            name: method_name,
-            documentation: fields_documentation,
-        };
+    fn collect_methods(
+        &self,
+        impl_items: Vec<ImplItem>,
+        respect_visibility: bool,
+        diagnostics: &mut Diagnostics,
+    ) -> Vec<Method> {
+        impl_items
+            .into_iter()
+            .filter_map(|item| {
+                if let ImplItem::Macro(impl_item_macro) = &item {
+                    diagnostics.warn(format!(
+                        "Skipped macro invocation '{}!' in impl block, its methods can't be documented",
+                        path_to_string(&impl_item_macro.mac.path)
+                    ));
+                }
+                if let ImplItem::Method(method) = item {
+                    if respect_visibility
+                        && !self.document_private_items
+                        && !is_public(&method.vis)
+                    {
+                        return None;
+                    }
+                    if self.is_excluded(&method.attrs) {
+                        return None;
+                    }
+                    let method_name = method.sig.ident.to_string();
+                    let parameters = self.format_parameters(&method.sig.inputs);
+                    let method_name = format!("{method_name}({parameters})");
+
+                    let mut method_documentation = String::new();
+                    for attribute in &method.attrs {
+                        let meta = attribute.parse_meta().unwrap();
+                        add_name_value_to_documentation(&mut method_documentation, meta);
+                    }
+                    if self.document_returns && !has_returns_section(&method_documentation) {
+                        if let Some(returns) = self.describe_return_type(&method.sig.output) {
+                            method_documentation.push_str(&format!("\n#### Returns\n{returns}\n"));
+                        }
+                    }
+                    if self.embed_source {
+                        let source = extract_source(&self.raw_rust_code, &method);
+                        method_documentation.push_str(&format!("\n```rust\n{source}\n```\n"));
+                    }
 
-        fields_vector.push(method);
+                    Some(Method {
+                        name: method_name,
+                        documentation: method_documentation,
+                        line: Some(method.sig.ident.span().start().line),
+                        visibility: respect_visibility.then(|| visibility_to_string(&method.vis)),
+                        optional_type: None,
+                        builder: returns_self(&method.sig.output),
+                        provided: false,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
     }
-    fields_vector
-}
 
-fn collect_methods(impl_items: Vec<ImplItem>) -> Vec<Method> {
-    impl_items
-        .into_iter()
-        .filter_map(|item| {
-            if let ImplItem::Method(method) = item {
-                let method_name = method.sig.ident.to_string();
-                let parameters: Vec<String> = method
-                    .sig
-                    .inputs
-                    .iter()
-                    .filter_map(|input| match input {
-                        syn::FnArg::Typed(pat_type) => {
-                            let parameter_name = match *pat_type.pat.clone() {
-                                syn::Pat::Ident(pat_ident) => pat_ident.ident.to_string(),
-                                _ => return None,
-                            };
-                            let parameter_type = pat_type.ty.clone();
-                            let parameter_type_string = quote!(#parameter_type).to_string();
-                            Some(format!("{}: {}", parameter_name, parameter_type_string))
+    fn collect_fields(&self, fields: &FieldsNamed) -> Vec<Method> {
+        let mut fields_vector = Vec::new();
+        for field in &fields.named {
+            if !self.document_private_items && !is_public(&field.vis) {
+                continue;
+            }
+            if self.is_excluded(&field.attrs) {
+                continue;
+            }
+            let method_name = field.ident.as_ref().unwrap().to_string();
+            let mut fields_documentation = String::new();
+
+            for attribute in &field.attrs {
+                let meta = attribute.parse_meta().unwrap();
+                add_name_value_to_documentation(&mut fields_documentation, meta);
+            }
+
+            let method = Method {
+                name: method_name,
+                documentation: fields_documentation,
+                line: field.ident.as_ref().map(|ident| ident.span().start().line),
+                visibility: Some(visibility_to_string(&field.vis)),
+                optional_type: self.option_inner_type(&field.ty),
+                builder: false,
+                provided: false,
+            };
+
+            fields_vector.push(method);
+        }
+        fields_vector
+    }
+
+    /// Returns true if one of the attributes is an excluded attribute or a
+    /// `#[doc(alias = "...")]` naming one.
+    fn is_excluded(&self, attributes: &[Attribute]) -> bool {
+        if self.exclude_attributes.is_empty() {
+            return false;
+        }
+        attributes.iter().any(|attribute| {
+            let name = path_to_string(&attribute.path);
+            if self.exclude_attributes.contains(&name) {
+                return true;
+            }
+            match attribute.parse_meta() {
+                Ok(Meta::List(meta_list)) if meta_list.path.is_ident("doc") => {
+                    meta_list.nested.iter().any(|nested| match nested {
+                        NestedMeta::Meta(Meta::NameValue(name_value))
+                            if name_value.path.is_ident("alias") =>
+                        {
+                            matches!(&name_value.lit, syn::Lit::Str(alias)
+                                if self.exclude_attributes.contains(&alias.value()))
                         }
-                        _ => None,
+                        _ => false,
                     })
-                    .collect();
-                let method_name = format!("{}({})", method_name, parameters.join(", "));
+                }
+                _ => false,
+            }
+        })
+    }
 
-                let mut method_documentation = String::new();
-                for attribute in &method.attrs {
-                    let meta = attribute.parse_meta().unwrap();
-                    add_name_value_to_documentation(&mut method_documentation, meta);
+    /// Formats the named parameters of a signature like `name: Type, age: u32`.
+    /// The receiver and parameters with patterns are left out.
+    fn format_parameters(&self, inputs: &Punctuated<FnArg, Comma>) -> String {
+        inputs
+            .iter()
+            .filter_map(|input| match input {
+                FnArg::Typed(pat_type) => {
+                    let parameter_name = match &*pat_type.pat {
+                        Pat::Ident(pat_ident) => pat_ident.ident.to_string(),
+                        _ => return None,
+                    };
+                    let parameter_type_string = self.type_to_string(&pat_type.ty);
+                    Some(format!("{}: {}", parameter_name, parameter_type_string))
                 }
+                _ => None,
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
 
-                Some(Method {
-                    name: method_name,
-                    documentation: method_documentation,
-                })
-            } else {
-                None
+    /// Returns the inner type `T` if the type is `Option<T>`.
+    fn option_inner_type(&self, ty: &Type) -> Option<String> {
+        let Type::Path(type_path) = ty else {
+            return None;
+        };
+        let segment = type_path.path.segments.last()?;
+        let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+            return None;
+        };
+        match arguments.args.iter().collect::<Vec<_>>().as_slice() {
+            [GenericArgument::Type(inner_type)] if segment.ident == "Option" => {
+                Some(self.type_to_string(inner_type))
             }
+            _ => None,
+        }
+    }
+
+    /// Renders a type, leaving out the paths of the standard library types unless
+    /// canonical paths are requested, see [shorten_std_paths].
+    fn type_to_string(&self, ty: &Type) -> String {
+        let type_string = tokens_to_string(quote!(#ty));
+        if self.canonical_paths {
+            type_string
+        } else {
+            shorten_std_paths(&type_string)
+        }
+    }
+
+    /// Describes a `Result` or `Option` return type by its inner types, e.g.
+    /// "Returns `T` on success, `E` on error." for `Result<T, E>`.
+    ///
+    /// Returns `None` for all other return types.
+    fn describe_return_type(&self, output: &ReturnType) -> Option<String> {
+        let ReturnType::Type(_, return_type) = output else {
+            return None;
+        };
+        let Type::Path(type_path) = &**return_type else {
+            return None;
+        };
+        let segment = type_path.path.segments.last()?;
+        let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+            return None;
+        };
+        let inner_types: Vec<String> = arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                GenericArgument::Type(inner_type) => Some(self.type_to_string(inner_type)),
+                _ => None,
+            })
+            .collect();
+
+        match (segment.ident.to_string().as_str(), inner_types.as_slice()) {
+            ("Result", [ok]) => Some(format!("Returns `{ok}` on success.")),
+            ("Result", [ok, err]) => Some(format!("Returns `{ok}` on success, `{err}` on error.")),
+            ("Option", [some]) => Some(format!("Returns `{some}` if present, otherwise `None`.")),
+            _ => None,
+        }
+    }
+}
+
+/// Collects a constant or static as `NAME: Type = value` with its documentation.
+/// The visibility is `None` for associated constants of trait impls.
+fn collect_constant(
+    ident: &Ident,
+    visibility: Option<&Visibility>,
+    ty: String,
+    value: String,
+    attributes: &[Attribute],
+) -> Method {
+    let mut documentation = String::new();
+    for attribute in attributes {
+        let meta = attribute.parse_meta().unwrap();
+        add_name_value_to_documentation(&mut documentation, meta);
+    }
+    Method {
+        name: format!("{ident}: {ty} = {value}"),
+        documentation,
+        line: Some(ident.span().start().line),
+        visibility: visibility.map(visibility_to_string),
+        optional_type: None,
+        builder: false,
+        provided: false,
+    }
+}
+
+/// Collects each path of a `use` declaration as re-export, with the documentation
+/// of the declaration.
+fn collect_reexports(item_use: &ItemUse) -> Vec<Method> {
+    let mut documentation = String::new();
+    for attribute in &item_use.attrs {
+        let meta = attribute.parse_meta().unwrap();
+        add_name_value_to_documentation(&mut documentation, meta);
+    }
+    let mut paths = Vec::new();
+    let prefix = if item_use.leading_colon.is_some() {
+        String::from("::")
+    } else {
+        String::new()
+    };
+    flatten_use_tree(&item_use.tree, prefix, &mut paths);
+    paths
+        .into_iter()
+        .map(|path| Method {
+            name: path,
+            documentation: documentation.clone(),
+            line: Some(item_use.use_token.span.start().line),
+            visibility: Some(visibility_to_string(&item_use.vis)),
+            optional_type: None,
+            builder: false,
+            provided: false,
         })
         .collect()
 }
 
+/// Flattens a use tree like `model::{job::Job, Class as Model}` into its paths,
+/// keeping the renames, e.g. `model::job::Job` and `model::Class as Model`.
+fn flatten_use_tree(tree: &UseTree, prefix: String, paths: &mut Vec<String>) {
+    match tree {
+        UseTree::Path(use_path) => flatten_use_tree(
+            &use_path.tree,
+            format!("{prefix}{}::", use_path.ident),
+            paths,
+        ),
+        UseTree::Name(use_name) => paths.push(format!("{prefix}{}", use_name.ident)),
+        UseTree::Rename(use_rename) => paths.push(format!(
+            "{prefix}{} as {}",
+            use_rename.ident, use_rename.rename
+        )),
+        UseTree::Glob(_) => paths.push(format!("{prefix}*")),
+        UseTree::Group(use_group) => {
+            for tree in &use_group.items {
+                flatten_use_tree(tree, prefix.clone(), paths);
+            }
+        }
+    }
+}
+
+/// Extracts the source code of the method from the raw source, without its doc
+/// comments and attributes. The common indentation of the lines is removed.
+fn extract_source(raw_rust_code: &str, method: &ImplItemMethod) -> String {
+    let start = match &method.vis {
+        Visibility::Inherited => method.sig.span().start(),
+        visibility => visibility.span().start(),
+    };
+    let end = method.block.span().end();
+    let lines: Vec<&str> = raw_rust_code
+        .lines()
+        .skip(start.line - 1)
+        .take(end.line - start.line + 1)
+        .collect();
+    let indentation = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or_default();
+    lines
+        .iter()
+        .map(|line| line.get(indentation..).unwrap_or_default().trim_end())
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// The keywords which are followed by a space, also if a group follows, e.g. `&mut (u32, u32)`.
+const SPACED_KEYWORDS: &[&str] = &["as", "const", "dyn", "impl", "in", "mut", "where"];
+
+/// The operators which are surrounded by spaces if they follow a value.
+const BINARY_OPERATORS: &[&str] = &[
+    "=", "->", "=>", "+", "-", "*", "/", "%", "&", "|", "^", "&&", "||", "==", "!=", "<=", ">=",
+    "<<", "+=", "-=",
+];
+
+/// Leaves out the paths of the types of the standard library, so
+/// `std::sync::Arc<std::sync::Mutex<T>>` becomes `Arc<Mutex<T>>`. The paths of
+/// other crates are kept, as they may be needed to tell types apart.
+fn shorten_std_paths(type_string: &str) -> String {
+    let std_path =
+        Regex::new(r"(^|[^A-Za-z0-9_:])(?:::)?(?:std|core|alloc)::(?:[a-z_][a-z0-9_]*::)*([A-Z])")
+            .unwrap();
+    std_path.replace_all(type_string, "${1}${2}").to_string()
+}
+
+/// Converts tokens, e.g. of a type or an expression, to a string formatted the
+/// way it is written idiomatically.
+///
+/// `quote!(#ty).to_string()` puts spaces between all tokens, which renders
+/// `HashMap<String, Vec<u32>>` as `HashMap < String , Vec < u32 > >`.
+fn tokens_to_string(tokens: TokenStream) -> String {
+    let mut output = String::new();
+    // whether the previous token is a value, i.e. an identifier, literal or group
+    let mut after_value = false;
+    let mut space_before_next = false;
+    let mut token_trees = tokens.into_iter().peekable();
+    while let Some(token_tree) = token_trees.next() {
+        match token_tree {
+            TokenTree::Ident(ident) => {
+                if after_value && !output.ends_with('\'') || space_before_next {
+                    output.push(' ');
+                }
+                let ident = ident.to_string();
+                output.push_str(&ident);
+                after_value = true;
+                space_before_next = SPACED_KEYWORDS.contains(&ident.as_str());
+            }
+            TokenTree::Literal(literal) => {
+                if after_value || space_before_next {
+                    output.push(' ');
+                }
+                output.push_str(&literal.to_string());
+                after_value = true;
+                space_before_next = false;
+            }
+            TokenTree::Group(group) => {
+                let is_brace = group.delimiter() == Delimiter::Brace;
+                if space_before_next || (is_brace && !output.is_empty()) {
+                    output.push(' ');
+                }
+                let inner = tokens_to_string(group.stream());
+                match group.delimiter() {
+                    Delimiter::Parenthesis => output.push_str(&format!("({inner})")),
+                    Delimiter::Bracket => output.push_str(&format!("[{inner}]")),
+                    Delimiter::Brace => output.push_str(&format!("{{ {inner} }}")),
+                    Delimiter::None => output.push_str(&inner),
+                }
+                after_value = true;
+                space_before_next = false;
+            }
+            TokenTree::Punct(punct) => {
+                let mut operator = punct.as_char().to_string();
+                let mut spacing = punct.spacing();
+                while spacing == Spacing::Joint {
+                    match token_trees.peek() {
+                        Some(TokenTree::Punct(next)) => {
+                            operator.push(next.as_char());
+                            spacing = next.spacing();
+                            token_trees.next();
+                        }
+                        _ => break,
+                    }
+                }
+                match operator.as_str() {
+                    "," | ";" | ":" => {
+                        output.push_str(&operator);
+                        space_before_next = true;
+                    }
+                    operator if after_value && BINARY_OPERATORS.contains(&operator) => {
+                        output.push_str(&format!(" {operator}"));
+                        space_before_next = true;
+                    }
+                    operator => {
+                        if space_before_next {
+                            output.push(' ');
+                        }
+                        output.push_str(operator);
+                        space_before_next = false;
+                    }
+                }
+                // closing angle brackets end a type, e.g. before `+ Send`
+                after_value = operator.ends_with('>') && !matches!(operator.as_str(), "->" | "=>");
+            }
+        }
+    }
+    output
+}
+
+/// Formats the generic parameters of a type definition with their bounds, like
+/// `<T: Clone, const N: usize>`, but without the `where` clause.
+///
+/// # Returns
+///
+/// The formatted parameters, or an empty string for a type without generics.
+fn generic_parameters(generics: &Generics) -> String {
+    if generics.params.is_empty() {
+        return String::new();
+    }
+    tokens_to_string(quote!(#generics))
+}
+
+/// Returns true if the return type is `Self` or `&mut Self`, which is the mark of
+/// a builder method.
+fn returns_self(output: &ReturnType) -> bool {
+    let ReturnType::Type(_, return_type) = output else {
+        return false;
+    };
+    let return_type = match &**return_type {
+        Type::Reference(reference) if reference.mutability.is_some() => &*reference.elem,
+        return_type => return_type,
+    };
+    matches!(return_type, Type::Path(type_path) if type_path.qself.is_none() && type_path.path.is_ident("Self"))
+}
+
+/// Returns true if the documentation already has a heading named 'Returns'.
+fn has_returns_section(documentation: &str) -> bool {
+    documentation
+        .lines()
+        .any(|line| line.starts_with('#') && line.trim_start_matches('#').trim() == "Returns")
+}
+
+/// Returns true if the method has a `where Self: Sized` bound, which excludes it
+/// from trait objects.
+fn requires_sized_self(signature: &Signature) -> bool {
+    signature
+        .generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| where_clause.predicates.iter())
+        .any(|predicate| match predicate {
+            WherePredicate::Type(predicate_type) => {
+                is_self_type(&predicate_type.bounded_ty)
+                    && predicate_type.bounds.iter().any(is_sized_bound)
+            }
+            _ => false,
+        })
+}
+
+/// Returns true if the method can be called on a trait object, i.e. it has a
+/// receiver, no type parameters and doesn't return `Self` by value.
+fn is_dispatchable(signature: &Signature) -> bool {
+    let has_receiver = matches!(signature.inputs.first(), Some(FnArg::Receiver(_)));
+    let is_generic = signature
+        .generics
+        .params
+        .iter()
+        .any(|param| !matches!(param, GenericParam::Lifetime(_)));
+    let returns_self = matches!(&signature.output, ReturnType::Type(_, ty) if is_self_type(ty));
+    has_receiver && !is_generic && !returns_self
+}
+
+/// Returns true if the type is `Self`.
+fn is_self_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.qself.is_none() && type_path.path.is_ident("Self"))
+}
+
+/// Returns true if the bound is the trait `Sized`.
+fn is_sized_bound(bound: &TypeParamBound) -> bool {
+    matches!(bound, TypeParamBound::Trait(trait_bound)
+        if trait_bound.path.segments.last().is_some_and(|segment| segment.ident == "Sized"))
+}
+
+/// Renders all attributes except the doc comments as list below an `Attributes`
+/// label, e.g. `` - `#[derive(Debug, Clone)]` ``. Empty without such attributes.
+fn render_attributes(attributes: &[Attribute]) -> String {
+    let attributes: Vec<String> = attributes
+        .iter()
+        .filter(|attribute| !attribute.path.is_ident("doc"))
+        .map(|attribute| format!("- `{}`\n", tokens_to_string(quote!(#attribute))))
+        .collect();
+    if attributes.is_empty() {
+        String::new()
+    } else {
+        format!("**Attributes**\n\n{}\n", attributes.concat())
+    }
+}
+
+/// Returns true if the attributes contain `#[automatically_derived]`, which marks
+/// the impl blocks generated by a `#[derive]`, e.g. in the output of `cargo expand`.
+fn is_automatically_derived(attributes: &[Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attribute| attribute.path.is_ident("automatically_derived"))
+}
+
+/// Returns true if the attributes contain `#[derive(Default)]`.
+fn has_derive_default(attributes: &[Attribute]) -> bool {
+    attributes
+        .iter()
+        .any(|attribute| match attribute.parse_meta() {
+            Ok(Meta::List(meta_list)) if meta_list.path.is_ident("derive") => {
+                meta_list.nested.iter().any(is_default_path)
+            }
+            _ => false,
+        })
+}
+
+/// Returns true if the nested meta is the plain path `Default`.
+fn is_default_path(nested: &NestedMeta) -> bool {
+    matches!(nested, NestedMeta::Meta(Meta::Path(path)) if path.is_ident("Default"))
+}
+
+/// Collects the defaults of the fields given by `#[serde(default)]`, which uses
+/// the `Default` of the field type, or `#[serde(default = "path")]`, which calls
+/// the given function.
+///
+/// # Returns
+///
+/// The pairs of field name and formatted default.
+fn collect_serde_defaults(fields: &FieldsNamed) -> Vec<(String, String)> {
+    let mut defaults = Vec::new();
+    for field in &fields.named {
+        let field_name = field.ident.as_ref().unwrap().to_string();
+        for attribute in &field.attrs {
+            let Ok(Meta::List(meta_list)) = attribute.parse_meta() else {
+                continue;
+            };
+            if !meta_list.path.is_ident("serde") {
+                continue;
+            }
+            for nested in &meta_list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("default") => {
+                        defaults.push((field_name.clone(), String::from("`Default::default()`")));
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident("default") =>
+                    {
+                        if let syn::Lit::Str(lit_str) = &name_value.lit {
+                            defaults.push((field_name.clone(), format!("`{}()`", lit_str.value())));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    defaults
+}
+
+/// Returns true if the impl block implements the `Default` trait.
+fn is_default_impl(item_impl: &ItemImpl) -> bool {
+    item_impl
+        .trait_
+        .as_ref()
+        .and_then(|(_, path, _)| path.segments.last())
+        .is_some_and(|segment| segment.ident == "Default")
+}
+
+/// Collects the field initializers of the struct expressions constructing `Self`
+/// or the implementing type within a `Default` impl.
+///
+/// # Returns
+///
+/// The name of the implementing type and the pairs of field name and default expression.
+fn collect_default_expressions(item_impl: &ItemImpl) -> (String, Vec<(String, String)>) {
+    let type_name = impl_type_name(item_impl).unwrap_or_default();
+    let mut collector = DefaultExpressionCollector {
+        type_name: type_name.clone(),
+        defaults: Vec::new(),
+    };
+    collector.visit_item_impl(item_impl);
+    (type_name, collector.defaults)
+}
+
+/// Visitor collecting the field initializers of struct expressions of one type.
+struct DefaultExpressionCollector {
+    type_name: String,
+    defaults: Vec<(String, String)>,
+}
+
+impl<'ast> Visit<'ast> for DefaultExpressionCollector {
+    fn visit_expr_struct(&mut self, expr_struct: &'ast ExprStruct) {
+        let constructed_type = expr_struct
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default();
+        if constructed_type == "Self" || constructed_type == self.type_name {
+            for field_value in &expr_struct.fields {
+                if let Member::Named(ident) = &field_value.member {
+                    let expression = &field_value.expr;
+                    self.defaults.push((
+                        ident.to_string(),
+                        format!("`{}`", tokens_to_string(quote!(#expression))),
+                    ));
+                }
+            }
+        }
+        syn::visit::visit_expr_struct(self, expr_struct);
+    }
+}
+
+/// Appends the default value to the documentation of each field which has one.
+fn add_defaults_to_documentation(fields: &mut [Method], defaults: &[(String, String)]) {
+    for field in fields.iter_mut() {
+        let default = defaults.iter().rev().find(|(name, _)| *name == field.name);
+        if let Some((_, expression)) = default {
+            if !field.documentation.is_empty() {
+                field.documentation.push('\n');
+            }
+            field
+                .documentation
+                .push_str(&format!("Default: {expression}\n"));
+        }
+    }
+}
+
+/// Returns true if the visibility is `pub`. Restricted visibilities like
+/// `pub(crate)` are private to the users of the documented API.
+fn is_public(visibility: &Visibility) -> bool {
+    matches!(visibility, Visibility::Public(_))
+}
+
+/// Converts the visibility to its string representation as written in the
+/// source, e.g. `pub(crate)`, or `private` without visibility.
+fn visibility_to_string(visibility: &Visibility) -> String {
+    match visibility {
+        Visibility::Inherited => String::from("private"),
+        visibility => tokens_to_string(quote!(#visibility)),
+    }
+}
+
+/// Converts a path like `std::println` to its string representation.
+fn path_to_string(path: &Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<String>>()
+        .join("::")
+}
+
 fn add_name_value_to_documentation(documentation: &mut String, meta: Meta) {
+    if let Meta::List(meta_list) = &meta {
+        if meta_list.path.is_ident("doc") {
+            add_doc_aliases_to_documentation(documentation, meta_list);
+        }
+    }
     if let Meta::NameValue(name_value) = meta {
         if name_value.path.is_ident("doc") {
             if let syn::Lit::Str(lit_str) = name_value.lit {
-                documentation.push_str(lit_str.value().trim());
+                let line = strip_control_characters(&lit_str.value());
+                if is_in_html_block(documentation, &line) {
+                    // Keep the indentation, which is significant e.g. within `<pre>`.
+                    documentation.push_str(line.strip_prefix(' ').unwrap_or(&line).trim_end());
+                } else {
+                    documentation.push_str(line.trim());
+                }
                 documentation.push('\n');
             }
         }
     }
 }
 
+/// Adds the aliases of a `#[doc(alias = "...")]` or `#[doc(alias("...", "..."))]`
+/// as an "Also known as" paragraph, so the searched names are found in the output.
+fn add_doc_aliases_to_documentation(documentation: &mut String, meta_list: &MetaList) {
+    let mut aliases = Vec::new();
+    for nested in &meta_list.nested {
+        match nested {
+            NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("alias") => {
+                if let syn::Lit::Str(alias) = &name_value.lit {
+                    aliases.push(alias.value());
+                }
+            }
+            NestedMeta::Meta(Meta::List(alias_list)) if alias_list.path.is_ident("alias") => {
+                for alias in &alias_list.nested {
+                    if let NestedMeta::Lit(syn::Lit::Str(alias)) = alias {
+                        aliases.push(alias.value());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if aliases.is_empty() {
+        return;
+    }
+    if !documentation.is_empty() && !documentation.ends_with("\n\n") {
+        documentation.push('\n');
+    }
+    let aliases: Vec<String> = aliases.iter().map(|alias| format!("`{alias}`")).collect();
+    documentation.push_str(&format!("_Also known as: {}_\n\n", aliases.join(", ")));
+}
+
+/// Moves the link reference definitions, like `[docs]: https://docs.rs`, and the
+/// footnote definitions of the documentation together into one block at its end.
+///
+/// A definition can't interrupt a paragraph, so one written right below the text
+/// it belongs to would be rendered as text and its references would be broken.
+/// The lines continuing a footnote up to the next empty line move along with it,
+/// indented, as the doc comment lines are collected trimmed.
+fn gather_reference_definitions(documentation: &str) -> String {
+    let mut text = Vec::new();
+    let mut definitions = Vec::new();
+    let mut in_code_block = false;
+    let mut in_footnote = false;
+    for line in documentation.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            in_footnote = false;
+        }
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            in_footnote = false;
+        }
+        if !in_code_block && is_reference_definition(trimmed) {
+            in_footnote = trimmed.starts_with("[^");
+            definitions.push(trimmed.to_string());
+        } else if in_footnote {
+            definitions.push(format!("    {trimmed}"));
+        } else {
+            text.push(line);
+        }
+    }
+    if definitions.is_empty() {
+        return documentation.to_string();
+    }
+    format!(
+        "{}\n\n{}\n",
+        text.join("\n").trim_end(),
+        definitions.join("\n")
+    )
+}
+
+/// Returns true if the line is a link reference definition, like
+/// `[docs]: https://docs.rs`, or a footnote definition, like `[^note]: Text`.
+pub fn is_reference_definition(line: &str) -> bool {
+    let Some((label, rest)) = line.strip_prefix('[').and_then(|rest| rest.split_once(']')) else {
+        return false;
+    };
+    !label.is_empty()
+        && rest
+            .strip_prefix(':')
+            .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+}
+
+/// Removes the control characters except tab and newline from a doc comment line.
+///
+/// Machine generated code sometimes contains doc strings with e.g. null bytes,
+/// which break the PlantUML and pandoc stages.
+fn strip_control_characters(line: &str) -> String {
+    line.chars()
+        .filter(|character| !character.is_control() || matches!(character, '\t' | '\n'))
+        .collect()
+}
+
+/// Returns true if the line belongs to an HTML block of the documentation.
+///
+/// As in Markdown, an HTML block starts with a line beginning with a tag and
+/// ends at the next empty line.
+fn is_in_html_block(documentation: &str, line: &str) -> bool {
+    let current_block = documentation.rsplit("\n\n").next().unwrap_or_default();
+    let block_start = if current_block.trim().is_empty() {
+        line
+    } else {
+        current_block
+    };
+    block_start.trim_start().starts_with('<')
+}
+
 #[cfg(test)]
 mod tests {
     use crate::model::class_object;
+    use crate::renderer::markdown_renderer::MarkdownRenderer;
+    use crate::renderer::Renderer;
 
     use super::*;
 
+    impl RustDocParser {
+        /// Parses the documentation and renders it like the Markdown output
+        /// format, without a diagram.
+        fn parse_code_doc_to_markdown_string(&self) -> String {
+            MarkdownRenderer::default().render(&[self.parse_code_doc()])
+        }
+
+        /// Parses the documentation, ignoring the warnings.
+        fn parse_code_doc(&self) -> Class {
+            self.parse_code_doc_with_diagnostics(&mut Diagnostics::default())
+        }
+    }
+
     /// Code string to use it in the tests
     fn test_rust_code() -> String {
         String::from(
@@ -205,48 +1242,230 @@ mod tests {
 
     #[test]
     fn test_parse_code_doc_to_markdown_string_struct_title() {
-        let expected_markdown =
+        let expected_title =
             String::from("## TestStruct\n\nThis is a doc comment\nover multiple lines\n\n");
 
         let parser = RustDocParser {
             raw_rust_code: test_rust_code(),
+            ..Default::default()
         };
         let markdown = parser.parse_code_doc_to_markdown_string();
 
-        assert_eq!(markdown, expected_markdown);
+        assert!(markdown.starts_with(&expected_title));
     }
 
     #[test]
-    fn test_parse_code_doc_name_and_documentation() {
-        let expected_struct_name = String::from("TestStruct");
-        let expected_struct_documentation =
-            String::from("This is a doc comment\nover multiple lines\n\n");
-
+    fn test_parse_code_doc_to_markdown_string_has_methods() {
         let parser = RustDocParser {
             raw_rust_code: test_rust_code(),
+            ..Default::default()
         };
-        let class_object = parser.parse_code_doc();
+        let markdown = parser.parse_code_doc_to_markdown_string();
 
-        assert_eq!(class_object.name, expected_struct_name);
-        assert_eq!(class_object.documentation, expected_struct_documentation);
+        assert!(markdown
+            .contains("\n### new(field1: String, field2: String)\nCreate a new TestStruct\n"));
+        assert!(markdown.contains("\n### another_method()\nAnother method\n"));
+        assert!(!markdown.contains("```plantuml"));
     }
 
     #[test]
-    fn test_parse_code_field_name_and_documentation() {
-        let expected_fields = vec![
-            class_object::Method {
-                name: "field1".to_string(),
-                documentation: "This is a doc comment of field1\n".to_string(),
-            },
-            class_object::Method {
-                name: "field2".to_string(),
+    fn test_parse_code_doc_documents_items_of_inline_modules() {
+        let rust_code = String::from(
+            r#"
+            pub mod model {
+                /// An order.
+                pub struct Order { pub id: u32 }
+                impl Order {
+                    /// Sums up the items.
+                    pub fn total(&self) -> u32 { 0 }
+                }
+            }
+            "#,
+        );
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+
+        let class = parser.parse_code_doc();
+
+        assert_eq!(class.name, "Order");
+        assert_eq!(class.documentation, "An order.\n\n");
+        assert_eq!(class.fields[0].name, "id");
+        assert_eq!(class.methods[0].name, "total()");
+    }
+
+    #[test]
+    fn test_gather_reference_definitions() {
+        let documentation = "Parses it, see [the spec][spec] and[^note].\n\
+            [spec]: https://example.com/spec\n\nMore text.\n```text\n[not]: a definition\n```\n\
+            [^note]: Only UTF-8\nis supported.\n\nLast paragraph.\n";
+
+        assert_eq!(
+            gather_reference_definitions(documentation),
+            "Parses it, see [the spec][spec] and[^note].\n\nMore text.\n```text\n\
+            [not]: a definition\n```\n\nLast paragraph.\n\n[spec]: https://example.com/spec\n\
+            [^note]: Only UTF-8\n    is supported.\n"
+        );
+        assert_eq!(gather_reference_definitions("No links.\n"), "No links.\n");
+    }
+
+    #[test]
+    fn test_parse_code_doc_gathers_multi_line_footnotes() {
+        let parser = RustDocParser {
+            raw_rust_code: String::from(
+                r#"
+                /// Decodes the input[^utf8].
+                /// [^utf8]: Only UTF-8
+                ///     is supported.
+                ///
+                /// Fails on invalid input.
+                pub struct Decoder {}
+                "#,
+            ),
+            ..Default::default()
+        };
+
+        let class = parser.parse_code_doc();
+
+        assert_eq!(
+            class.documentation,
+            "Decodes the input[^utf8].\n\nFails on invalid input.\n\n\
+            [^utf8]: Only UTF-8\n    is supported.\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_skips_items_local_to_functions() {
+        let rust_code = String::from(
+            r#"
+            /// A parser.
+            pub struct Parser { pub input: String }
+            impl Parser {
+                /// Parses the input.
+                pub fn parse(&self) {
+                    /// A token local to the method.
+                    struct Token { pub text: String }
+                }
+            }
+            fn helper() {
+                /// A state local to the function.
+                pub struct State { pub position: usize }
+                impl State {
+                    pub fn advance(&mut self) {}
+                }
+            }
+            "#,
+        );
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+
+        let class = parser.parse_code_doc();
+
+        assert_eq!(class.name, "Parser");
+        assert_eq!(class.documentation, "A parser.\n\n");
+        let field_names: Vec<&str> = class
+            .fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect();
+        assert_eq!(field_names, vec!["input"]);
+        let method_names: Vec<&str> = class
+            .methods
+            .iter()
+            .map(|method| method.name.as_str())
+            .collect();
+        assert_eq!(method_names, vec!["parse()"]);
+    }
+
+    #[test]
+    fn test_parse_code_doc_skips_automatically_derived_impls() {
+        let rust_code = String::from(
+            r#"
+            pub struct Point { pub x: i32 }
+            #[automatically_derived]
+            impl ::core::clone::Clone for Point {
+                #[inline]
+                fn clone(&self) -> Point { Point { x: self.x } }
+            }
+            /// Shows the point.
+            impl fmt::Display for Point {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { Ok(()) }
+            }
+            "#,
+        );
+        let parser = RustDocParser {
+            raw_rust_code: rust_code.clone(),
+            ..Default::default()
+        };
+        let labeling_parser = RustDocParser {
+            raw_rust_code: rust_code,
+            document_derived_impls: true,
+            ..Default::default()
+        };
+
+        let class = parser.parse_code_doc();
+        let labeled_class = labeling_parser.parse_code_doc();
+
+        let trait_names: Vec<&str> = class
+            .trait_impls
+            .iter()
+            .map(|trait_impl| trait_impl.name.as_str())
+            .collect();
+        assert_eq!(trait_names, vec!["fmt::Display"]);
+        assert_eq!(labeled_class.trait_impls.len(), 2);
+        assert_eq!(labeled_class.trait_impls[0].name, "core::clone::Clone");
+        assert!(labeled_class.trait_impls[0]
+            .documentation
+            .starts_with("_Automatically derived_\n"));
+    }
+
+    #[test]
+    fn test_parse_code_doc_name_and_documentation() {
+        let expected_struct_name = String::from("TestStruct");
+        let expected_struct_documentation =
+            String::from("This is a doc comment\nover multiple lines\n\n");
+
+        let parser = RustDocParser {
+            raw_rust_code: test_rust_code(),
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(class_object.name, expected_struct_name);
+        assert_eq!(class_object.documentation, expected_struct_documentation);
+    }
+
+    #[test]
+    fn test_parse_code_field_name_and_documentation() {
+        let expected_fields = vec![
+            class_object::Method {
+                name: "field1".to_string(),
+                documentation: "This is a doc comment of field1\n".to_string(),
+                line: Some(6),
+                visibility: Some("private".to_string()),
+                optional_type: None,
+                builder: false,
+                provided: false,
+            },
+            class_object::Method {
+                name: "field2".to_string(),
                 documentation: "This is a doc comment of field2\n".to_string(),
+                line: Some(8),
+                visibility: Some("private".to_string()),
+                optional_type: None,
+                builder: false,
+                provided: false,
             },
         ];
         let expected_amount_of_fields = expected_fields.len();
 
         let parser = RustDocParser {
             raw_rust_code: test_rust_code(),
+            document_private_items: true,
+            ..Default::default()
         };
         let class_object = parser.parse_code_doc();
 
@@ -260,20 +1479,942 @@ mod tests {
             class_object::Method {
                 name: "new(field1: String, field2: String)".to_string(),
                 documentation: "Create a new TestStruct\n".to_string(),
+                line: Some(13),
+                visibility: Some("pub".to_string()),
+                optional_type: None,
+                builder: true,
+                provided: false,
             },
             class_object::Method {
                 name: "another_method()".to_string(),
                 documentation: "Another method\n".to_string(),
+                line: Some(20),
+                visibility: Some("pub".to_string()),
+                optional_type: None,
+                builder: true,
+                provided: false,
             },
         ];
         let expected_amount_of_fields = expected_methods.len();
 
         let parser = RustDocParser {
             raw_rust_code: test_rust_code(),
+            ..Default::default()
         };
         let class_object = parser.parse_code_doc();
 
         assert_eq!(class_object.methods.len(), expected_amount_of_fields);
         assert_eq!(class_object.methods, expected_methods);
     }
+
+    #[test]
+    fn test_parse_code_doc_preserves_html_blocks() {
+        let rust_code = String::from(
+            r#"
+            /// A matrix.<br>
+            /// Stored row by row.
+            ///
+            /// <table>
+            ///   <tr><td>1</td><td>0</td></tr>
+            ///   <tr><td>0</td><td>1</td></tr>
+            /// </table>
+            ///
+            /// <pre>
+            ///     identity
+            /// </pre>
+            struct Matrix {}
+            "#,
+        );
+        let expected_documentation = "A matrix.<br>\nStored row by row.\n\n\
+            <table>\n  <tr><td>1</td><td>0</td></tr>\n  <tr><td>0</td><td>1</td></tr>\n</table>\n\n\
+            <pre>\n    identity\n</pre>\n\n";
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(class_object.documentation, expected_documentation);
+    }
+
+    #[test]
+    fn test_parse_code_doc_warns_about_macro_invocations() {
+        let rust_code = String::from(
+            r#"
+            struct Component { name: String }
+            yew::html! { <Component name="generated" /> }
+            impl Component {
+                generate_getters!();
+                fn render(&self) {}
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            document_private_items: true,
+            ..Default::default()
+        };
+        let mut diagnostics = Diagnostics::default();
+        let class_object = parser.parse_code_doc_with_diagnostics(&mut diagnostics);
+
+        assert_eq!(class_object.name, "Component");
+        assert_eq!(class_object.methods.len(), 1);
+        assert_eq!(diagnostics.warnings().len(), 2);
+        assert!(diagnostics.warnings()[0].contains("'yew::html!'"));
+        assert!(diagnostics.warnings()[1].contains("'generate_getters!'"));
+    }
+
+    #[test]
+    fn test_parse_code_doc_documents_defaults_of_default_impl() {
+        let rust_code = String::from(
+            r#"
+            struct Person {
+                /// The name.
+                name: String,
+                age: u32,
+                email: Option<String>,
+            }
+            impl Default for Person {
+                fn default() -> Self {
+                    Self {
+                        name: String::new(),
+                        age: 0,
+                        email: None,
+                    }
+                }
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            document_private_items: true,
+            show_defaults: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(
+            class_object.fields[0].documentation,
+            "The name.\n\nDefault: `String::new()`\n"
+        );
+        assert_eq!(class_object.fields[1].documentation, "Default: `0`\n");
+        assert_eq!(class_object.fields[2].documentation, "Default: `None`\n");
+    }
+
+    #[test]
+    fn test_parse_code_doc_matches_generic_impls_to_generic_struct() {
+        let rust_code = String::from(
+            r#"
+            pub struct Wrapper<T> {
+                pub inner: Vec<T>,
+            }
+            impl<T> Wrapper<T> {
+                pub fn len(&self) -> usize {}
+            }
+            impl<T> Default for Wrapper<T> {
+                fn default() -> Self {
+                    Wrapper { inner: Vec::new() }
+                }
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            show_defaults: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(class_object.name, "Wrapper<T>");
+        assert_eq!(class_object.methods[0].name, "len()");
+        assert_eq!(
+            class_object.fields[0].documentation,
+            "Default: `Vec::new()`\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_merges_impls_in_declaration_order() {
+        let rust_code = String::from(
+            r#"
+            pub struct Order {}
+            pub struct Customer {}
+            impl Customer {
+                pub fn name(&self) {}
+            }
+            impl Order {
+                pub fn total(&self) {}
+            }
+            impl Customer {
+                pub fn email(&self) {}
+            }
+            "#,
+        );
+
+        let method_names = |merge_impls_into_declaration_order: bool| -> Vec<String> {
+            let parser = RustDocParser {
+                raw_rust_code: rust_code.clone(),
+                merge_impls_into_declaration_order,
+                ..Default::default()
+            };
+            parser
+                .parse_code_doc()
+                .methods
+                .into_iter()
+                .map(|method| method.name)
+                .collect()
+        };
+
+        assert_eq!(method_names(false), vec!["name()", "total()", "email()"]);
+        assert_eq!(method_names(true), vec!["total()", "name()", "email()"]);
+    }
+
+    #[test]
+    fn test_parse_code_doc_documents_derived_defaults() {
+        let rust_code = String::from(
+            r#"
+            #[derive(Debug, Default)]
+            struct Person {
+                age: u32,
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            document_private_items: true,
+            show_defaults: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(
+            class_object.fields[0].documentation,
+            "Default: `Default::default()` (derived)\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_documents_serde_defaults() {
+        let rust_code = String::from(
+            r#"
+            struct Config {
+                #[serde(default)]
+                retries: u32,
+                #[serde(default = "default_timeout")]
+                timeout: u64,
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            document_private_items: true,
+            show_defaults: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(
+            class_object.fields[0].documentation,
+            "Default: `Default::default()`\n"
+        );
+        assert_eq!(
+            class_object.fields[1].documentation,
+            "Default: `default_timeout()`\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_hides_defaults_by_default() {
+        let rust_code = String::from(
+            r#"
+            #[derive(Default)]
+            struct Person {
+                age: u32,
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            document_private_items: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(class_object.fields[0].documentation, "");
+    }
+
+    #[test]
+    fn test_parse_code_doc_documents_result_and_option_returns() {
+        let rust_code = String::from(
+            r#"
+            struct Person {}
+            impl Person {
+                /// Loads a person.
+                fn load(path: &str) -> Result<Person, IoError> {}
+                fn find(name: &str) -> Option<Person> {}
+                /// Saves the person.
+                ///
+                /// # Returns
+                /// Nothing of interest.
+                fn save(&self) -> Result<(), IoError> {}
+                fn name(&self) -> String {}
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            document_private_items: true,
+            document_returns: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(
+            class_object.methods[0].documentation,
+            "Loads a person.\n\n#### Returns\nReturns `Person` on success, `IoError` on error.\n"
+        );
+        assert_eq!(
+            class_object.methods[1].documentation,
+            "\n#### Returns\nReturns `Person` if present, otherwise `None`.\n"
+        );
+        assert!(!class_object.methods[2].documentation.contains("on success"));
+        assert_eq!(class_object.methods[3].documentation, "");
+    }
+
+    #[test]
+    fn test_parse_code_doc_marks_builder_methods() {
+        let rust_code = String::from(
+            r#"
+            struct Request {}
+            impl Request {
+                fn new() -> Self {}
+                fn timeout(&mut self, secs: u64) -> &mut Self {}
+                fn header(&self) -> &Self {}
+                fn send(self) -> Response {}
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            document_private_items: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        let builders: Vec<bool> = class_object
+            .methods
+            .iter()
+            .map(|method| method.builder)
+            .collect();
+        assert_eq!(builders, vec![true, true, false, false]);
+    }
+
+    #[test]
+    fn test_parse_code_doc_collects_constants_and_statics() {
+        let rust_code = String::from(
+            r#"
+            /// The maximum number of items.
+            static MAX: usize = 100;
+            /// The version.
+            const VERSION: &str = "1.0";
+            struct Config {}
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            document_private_items: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(
+            class_object.constants,
+            vec![
+                class_object::Method {
+                    name: "MAX: usize = 100".to_string(),
+                    documentation: "The maximum number of items.\n".to_string(),
+                    line: Some(3),
+                    visibility: Some("private".to_string()),
+                    optional_type: None,
+                    builder: false,
+                    provided: false,
+                },
+                class_object::Method {
+                    name: "VERSION: &str = \"1.0\"".to_string(),
+                    documentation: "The version.\n".to_string(),
+                    line: Some(5),
+                    visibility: Some("private".to_string()),
+                    optional_type: None,
+                    builder: false,
+                    provided: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_documents_only_public_items_by_default() {
+        let rust_code = String::from(
+            r#"
+            pub struct Person {
+                pub name: String,
+                pub(crate) id: u32,
+                age: u32,
+            }
+            impl Person {
+                pub fn new() -> Self {}
+                fn validate(&self) {}
+            }
+            pub const MAX_AGE: u32 = 150;
+            const MIN_AGE: u32 = 0;
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(class_object.fields.len(), 1);
+        assert_eq!(class_object.fields[0].name, "name");
+        assert_eq!(class_object.methods.len(), 1);
+        assert_eq!(class_object.methods[0].name, "new()");
+        assert_eq!(class_object.constants.len(), 1);
+        assert_eq!(class_object.constants[0].name, "MAX_AGE: u32 = 150");
+    }
+
+    #[test]
+    fn test_parse_code_doc_collects_trait_impl_constants_and_methods() {
+        let rust_code = String::from(
+            r#"
+            pub struct Person {}
+            impl Identify for Person {
+                /// The identifier of all persons.
+                const ID: u32 = 5;
+                /// Returns the identifier.
+                fn id(&self) -> u32 {}
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert!(class_object.methods.is_empty());
+        assert_eq!(
+            class_object.trait_impls,
+            vec![class_object::TraitImpl {
+                name: "Identify".to_string(),
+                documentation: String::new(),
+                constants: vec![class_object::Method {
+                    name: "ID: u32 = 5".to_string(),
+                    documentation: "The identifier of all persons.\n".to_string(),
+                    line: Some(5),
+                    visibility: None,
+                    optional_type: None,
+                    builder: false,
+                    provided: false,
+                }],
+                methods: vec![class_object::Method {
+                    name: "id()".to_string(),
+                    documentation: "Returns the identifier.\n".to_string(),
+                    line: Some(7),
+                    visibility: None,
+                    optional_type: None,
+                    builder: false,
+                    provided: false,
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_lists_implemented_traits() {
+        let rust_code = String::from(
+            r#"
+            pub struct Person { pub name: String }
+            impl fmt::Display for Person {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {}
+            }
+            impl Clone for Person {
+                fn clone(&self) -> Self {}
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        let trait_names: Vec<&str> = class_object
+            .trait_impls
+            .iter()
+            .map(|trait_impl| trait_impl.name.as_str())
+            .collect();
+        assert_eq!(trait_names, vec!["fmt::Display", "Clone"]);
+    }
+
+    #[test]
+    fn test_parse_code_doc_shortens_std_paths_unless_canonical() {
+        let rust_code = String::from(
+            r#"
+            pub struct Registry {}
+            impl Registry {
+                pub fn lookup(&self, names: std::collections::HashMap<String, ::std::sync::Arc<ext::Name>>) -> core::option::Option<Entry> {}
+            }
+            "#,
+        );
+        let parser = RustDocParser {
+            raw_rust_code: rust_code.clone(),
+            ..Default::default()
+        };
+        let canonical_parser = RustDocParser {
+            raw_rust_code: rust_code,
+            canonical_paths: true,
+            ..Default::default()
+        };
+
+        let class = parser.parse_code_doc();
+        let canonical_class = canonical_parser.parse_code_doc();
+
+        assert_eq!(
+            class.methods[0].name,
+            "lookup(names: HashMap<String, Arc<ext::Name>>)"
+        );
+        assert_eq!(
+            canonical_class.methods[0].name,
+            "lookup(names: std::collections::HashMap<String, ::std::sync::Arc<ext::Name>>)"
+        );
+    }
+
+    #[test]
+    fn test_tokens_to_string_formats_types_idiomatically() {
+        let cases = [
+            "HashMap<String, Vec<u32>>",
+            "&'a mut [u8; 4]",
+            "Box<dyn Fn(u32) -> Result<(), Error> + Send>",
+            "Option<&(u32, u32)>",
+            "std::collections::BTreeMap<K, V>",
+            "vec![1, -2]",
+            "Config { retries: 3 }",
+            "1 << 4",
+        ];
+
+        for case in cases {
+            let tokens: TokenStream = case.parse().unwrap();
+            assert_eq!(tokens_to_string(tokens), case);
+        }
+    }
+
+    #[test]
+    fn test_parse_code_doc_renders_const_generics_in_struct_name() {
+        let rust_code = String::from(
+            r#"
+            /// A fixed size buffer.
+            pub struct Buffer<T: Copy, const N: usize> where T: Default {
+                data: [T; N],
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(class_object.name, "Buffer<T: Copy, const N: usize>");
+        assert!(parser
+            .parse_code_doc_to_markdown_string()
+            .starts_with("## Buffer<T: Copy, const N: usize>\n"));
+    }
+
+    #[test]
+    fn test_parse_code_doc_renders_nested_generic_types() {
+        let rust_code = String::from(
+            r#"
+            pub struct Registry {}
+            impl Registry {
+                pub fn insert(&mut self, entries: HashMap<String, Vec<u32>>) {}
+            }
+            pub const EMPTY: Option<Vec<u32>> = None;
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(
+            class_object.methods[0].name,
+            "insert(entries: HashMap<String, Vec<u32>>)"
+        );
+        assert_eq!(
+            class_object.constants[0].name,
+            "EMPTY: Option<Vec<u32>> = None"
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_strips_control_characters() {
+        let rust_code = String::from(
+            r#"
+            #[doc = "A null\u{0} byte,\ta tab and a bell\u{7}."]
+            pub struct Signal {}
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(
+            class_object.documentation,
+            "A null byte,\ta tab and a bell.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_shows_attributes() {
+        let rust_code = String::from(
+            r#"
+            /// A person.
+            #[derive(Debug, Clone)]
+            #[cfg(feature = "people")]
+            #[serde(rename_all = "camelCase")]
+            pub struct Person {}
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            show_attributes: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(
+            class_object.documentation,
+            "**Attributes**\n\n\
+            - `#[derive(Debug, Clone)]`\n\
+            - `#[cfg(feature = \"people\")]`\n\
+            - `#[serde(rename_all = \"camelCase\")]`\n\n\
+            A person.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_flags_option_fields_optional() {
+        let rust_code = String::from(
+            r#"
+            pub struct Person {
+                pub name: String,
+                pub age: Option<u32>,
+                pub nickname: std::option::Option<String>,
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        let optional_types: Vec<Option<&str>> = class_object
+            .fields
+            .iter()
+            .map(|field| field.optional_type.as_deref())
+            .collect();
+        assert_eq!(optional_types, vec![None, Some("u32"), Some("String")]);
+        let markdown = MarkdownRenderer::default().render(&[class_object]);
+        assert!(markdown.contains("\n### age (optional): u32\n"));
+    }
+
+    #[test]
+    fn test_parse_code_doc_collects_traits_with_object_safety() {
+        let rust_code = String::from(
+            r#"
+            /// A shape.
+            pub trait Shape {
+                /// The area of the shape.
+                fn area(&self) -> f64;
+                /// Creates a copy of the shape.
+                fn duplicate(&self) -> Self where Self: Sized;
+            }
+            pub trait Factory {
+                fn create() -> Self;
+            }
+            pub trait Visitor {
+                fn visit<T: Shape>(&mut self, shape: &T);
+            }
+            trait Internal {}
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        let traits: Vec<(&str, bool)> = class_object
+            .traits
+            .iter()
+            .map(|defined_trait| (defined_trait.name.as_str(), defined_trait.object_safe))
+            .collect();
+        assert_eq!(
+            traits,
+            vec![("Shape", true), ("Factory", false), ("Visitor", false)]
+        );
+        let shape = &class_object.traits[0];
+        assert_eq!(shape.documentation, "A shape.\n");
+        assert_eq!(shape.methods[0].name, "area()");
+        assert_eq!(shape.methods[1].name, "duplicate()");
+        assert!(shape.methods[1]
+            .documentation
+            .ends_with("Requires `Self: Sized`, so it can't be called on a trait object.\n"));
+    }
+
+    #[test]
+    fn test_parse_code_doc_distinguishes_required_and_provided_trait_methods() {
+        let rust_code = String::from(
+            r#"
+            pub trait Greeter {
+                /// The name to greet.
+                fn name(&self) -> String;
+                /// Greets by name.
+                fn greet(&self) -> String {
+                    format!("Hello, {}!", self.name())
+                }
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        let greeter = &class_object.traits[0];
+        assert!(!greeter.methods[0].provided);
+        assert!(greeter.methods[1].provided);
+        let markdown = parser.parse_code_doc_to_markdown_string();
+        assert!(markdown.contains("#### name() (required)\n"));
+        assert!(markdown.contains("#### greet() (default)\n"));
+    }
+
+    #[test]
+    fn test_parse_code_doc_collects_reexports() {
+        let rust_code = String::from(
+            r#"
+            /// The model of the documentation.
+            pub use crate::model::{class_object::Class as Model, job::*};
+            pub(crate) use std::fmt::Display;
+            use std::collections::HashMap;
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        let reexports: Vec<&str> = class_object
+            .reexports
+            .iter()
+            .map(|reexport| reexport.name.as_str())
+            .collect();
+        assert_eq!(
+            reexports,
+            vec![
+                "crate::model::class_object::Class as Model",
+                "crate::model::job::*"
+            ]
+        );
+        assert_eq!(
+            class_object.reexports[0].documentation,
+            "The model of the documentation.\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_renders_doc_aliases() {
+        let rust_code = String::from(
+            r#"
+            struct Factory {}
+            impl Factory {
+                /// Creates a widget.
+                #[doc(alias = "make")]
+                pub fn create() {}
+                #[doc(alias("build", "construct"))]
+                pub fn assemble() {}
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(
+            class_object.methods[0].documentation,
+            "Creates a widget.\n\n_Also known as: `make`_\n\n"
+        );
+        assert_eq!(
+            class_object.methods[1].documentation,
+            "_Also known as: `build`, `construct`_\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_code_doc_skips_items_with_excluded_attribute() {
+        let rust_code = String::from(
+            r#"
+            pub struct Worker {
+                pub name: String,
+                #[internal]
+                pub state: u32,
+            }
+            impl Worker {
+                pub fn run(&self) {}
+                #[doc(alias = "internal")]
+                pub fn reset(&mut self) {}
+                #[internal]
+                pub fn debug_dump(&self) {}
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            exclude_attributes: vec![String::from("internal")],
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(class_object.fields.len(), 1);
+        assert_eq!(class_object.fields[0].name, "name");
+        assert_eq!(class_object.methods.len(), 1);
+        assert_eq!(class_object.methods[0].name, "run()");
+    }
+
+    #[test]
+    fn test_parse_code_doc_captures_line_and_visibility() {
+        let rust_code = String::from(
+            r#"
+            pub struct Person {
+                pub(crate) id: u32,
+            }
+
+            impl Person {
+                /// Creates a person.
+                pub fn new() -> Self {}
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            document_private_items: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(class_object.fields[0].line, Some(3));
+        assert_eq!(
+            class_object.fields[0].visibility.as_deref(),
+            Some("pub(crate)")
+        );
+        assert_eq!(class_object.methods[0].line, Some(8));
+        assert_eq!(class_object.methods[0].visibility.as_deref(), Some("pub"));
+    }
+
+    #[test]
+    fn test_parse_code_doc_collects_extern_block() {
+        let rust_code = String::from(
+            r#"
+            pub struct Library {}
+            extern "C" {
+                /// Computes the checksum of the buffer.
+                pub fn checksum(buffer: *const u8, length: usize) -> u32;
+                /// The version of the library.
+                pub static VERSION: c_int;
+                fn internal();
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(class_object.foreign_mods.len(), 1);
+        let foreign_mod = &class_object.foreign_mods[0];
+        assert_eq!(foreign_mod.abi, "C");
+        assert_eq!(foreign_mod.items.len(), 2);
+        assert_eq!(
+            foreign_mod.items[0].name,
+            "checksum(buffer: *const u8, length: usize) -> u32"
+        );
+        assert_eq!(
+            foreign_mod.items[0].documentation,
+            "Computes the checksum of the buffer.\n"
+        );
+        assert_eq!(foreign_mod.items[1].name, "static VERSION: c_int");
+    }
+
+    #[test]
+    fn test_parse_code_doc_embeds_method_source() {
+        let rust_code = String::from(
+            r#"
+            struct Counter { count: u32 }
+            impl Counter {
+                /// Increments the counter.
+                #[inline]
+                pub fn increment(&mut self) {
+                    self.count += 1;
+                }
+            }
+            "#,
+        );
+
+        let parser = RustDocParser {
+            raw_rust_code: rust_code,
+            embed_source: true,
+            ..Default::default()
+        };
+        let class_object = parser.parse_code_doc();
+
+        assert_eq!(
+            class_object.methods[0].documentation,
+            "Increments the counter.\n\n```rust\npub fn increment(&mut self) {\n    self.count += 1;\n}\n```\n"
+        );
+    }
 }
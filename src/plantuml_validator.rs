@@ -0,0 +1,145 @@
+//! Syntax check of the generated PlantUML by the `plantuml` executable.
+
+use std::io::{self, ErrorKind, Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use std::{env, thread};
+
+/// The time the syntax check may take, unless configured otherwise.
+const DEFAULT_PLANTUML_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Checks diagrams with the syntax check of the `plantuml` executable, so
+/// malformed diagrams are found while generating instead of when rendering them.
+pub struct PlantumlValidator {
+    plantuml_path: String,
+    timeout: Duration,
+}
+
+impl PlantumlValidator {
+    /// Creates a new `PlantumlValidator`.
+    ///
+    /// # Arguments
+    ///
+    /// `plantuml_path` - An optional path to the `plantuml` executable.
+    /// If `None`, it will look for the `PLANTUML_PATH` environment variable.
+    /// If the environment variable is also not set, it defaults to "plantuml".
+    pub fn new(plantuml_path: Option<String>) -> Self {
+        let plantuml_path = plantuml_path.unwrap_or_else(|| {
+            env::var("PLANTUML_PATH").unwrap_or_else(|_| String::from("plantuml"))
+        });
+        PlantumlValidator {
+            plantuml_path,
+            timeout: DEFAULT_PLANTUML_TIMEOUT,
+        }
+    }
+
+    /// Sets the time the syntax check may take, after which `plantuml` is killed
+    /// and an error is returned. Defaults to 30 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Runs the diagram through `plantuml -syntax`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(None)` - The diagram is valid.
+    /// * `Ok(Some(String))` - The syntax error with the line it was found in.
+    /// * `Err(io::Error)` - The executable could not be run, e.g. as it isn't
+    ///   installed, or didn't finish within the timeout.
+    pub fn syntax_error(&self, plantuml: &str) -> io::Result<Option<String>> {
+        let mut child = Command::new(self.plantuml_path.as_str())
+            .arg("-syntax")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        // The pipes are served by threads, so a hanging plantuml can't block the timeout.
+        let mut stdin = child.stdin.take().unwrap();
+        let plantuml = plantuml.to_string();
+        let writer = thread::spawn(move || stdin.write_all(plantuml.as_bytes()));
+        let mut stdout = child.stdout.take().unwrap();
+        let reader = thread::spawn(move || {
+            let mut buffer = Vec::new();
+            stdout.read_to_end(&mut buffer).map(|_| buffer)
+        });
+
+        let deadline = Instant::now() + self.timeout;
+        while child.try_wait()?.is_none() {
+            if Instant::now() >= deadline {
+                child.kill()?;
+                child.wait()?;
+                return Err(io::Error::new(
+                    ErrorKind::TimedOut,
+                    format!(
+                        "PlantUML did not finish within {} seconds",
+                        self.timeout.as_secs_f32()
+                    ),
+                ));
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        // A diagram which isn't read completely is reported by the output of plantuml.
+        let _ = writer.join();
+        let stdout = reader.join().unwrap()?;
+        Ok(parse_syntax_check(&String::from_utf8_lossy(&stdout)))
+    }
+}
+
+/// Parses the output of `plantuml -syntax`, which starts with the type of the
+/// diagram if it is valid, or with `ERROR` followed by the line number and the
+/// message otherwise.
+fn parse_syntax_check(output: &str) -> Option<String> {
+    let mut lines = output.lines().map(str::trim);
+    if lines.next() != Some("ERROR") {
+        return None;
+    }
+    let line_number = lines.next().unwrap_or_default();
+    let message = lines.next().unwrap_or("Syntax Error?");
+    Some(format!("line {line_number}: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_syntax_check() {
+        assert_eq!(parse_syntax_check("CLASS\n(1 entities)\n"), None);
+        assert_eq!(
+            parse_syntax_check("ERROR\n3\nSyntax Error?\n"),
+            Some(String::from("line 3: Syntax Error?"))
+        );
+    }
+
+    #[test]
+    fn test_syntax_error_without_executable() {
+        let validator = PlantumlValidator::new(Some(String::from("/nonexistent/plantuml")));
+
+        assert!(validator.syntax_error("@startuml\n@enduml").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_syntax_error_times_out() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script_path = env::temp_dir().join(format!(
+            "rustitect_hanging_plantuml_{}.sh",
+            std::process::id()
+        ));
+        std::fs::write(&script_path, "#!/bin/sh\nsleep 5\n").unwrap();
+        std::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let validator = PlantumlValidator::new(Some(script_path.to_string_lossy().to_string()))
+            .with_timeout(Duration::from_millis(200));
+        let started = Instant::now();
+        let result = validator.syntax_error("@startuml\n@enduml");
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::TimedOut);
+        std::fs::remove_file(script_path).unwrap();
+    }
+}
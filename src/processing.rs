@@ -1,19 +1,48 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::ops::Add;
+use std::time::Duration;
 
 use regex::Regex;
 
-use crate::cli::{Cli, OutputFormat};
+use crate::cli::{Cli, InputKind, OutputFormat, SortOrder};
+use crate::diagnostics::{Diagnostics, Summary};
+use crate::model::class_object::{Class, Method};
+use crate::model::job::{Job, JobResult};
 use crate::parser::asciidoc_parser::AsciidocParser;
-use crate::parser::plantuml_parser::PlantumlParser;
-use crate::parser::rust_doc_parser::RustDocParser;
+use crate::parser::component_diagram_parser::ComponentDiagramParser;
+use crate::parser::layered_diagram_parser::LayeredDiagramParser;
+use crate::parser::markdown_parser::MarkdownParser;
+use crate::parser::plantuml_parser::{
+    diagram_methods, insert_preamble, method_diagram, PlantumlParser,
+};
+use crate::parser::rust_doc_parser::{is_reference_definition, RustDocParser};
+use crate::plantuml_validator::PlantumlValidator;
+use crate::renderer::asciidoc_renderer::AsciidocRenderer;
+use crate::renderer::json_renderer::JsonRenderer;
+use crate::renderer::markdown_renderer::MarkdownRenderer;
+use crate::renderer::plantuml_renderer::PlantumlRenderer;
+use crate::renderer::toml_renderer::TomlRenderer;
+use crate::renderer::Renderer;
 
 /// Processing struct that handles the processing of input based on the provided arguments.
 pub struct Processing {
     pub args: Cli,
+    /// The warnings raised while processing the input.
+    pub diagnostics: Diagnostics,
+    /// The counts of the documented types and members for the summary of the run.
+    pub summary: Summary,
 }
 
 impl Processing {
+    /// Creates a new `Processing` for the given command-line arguments.
+    pub fn new(args: Cli) -> Self {
+        Processing {
+            args,
+            diagnostics: Diagnostics::default(),
+            summary: Summary::default(),
+        }
+    }
+
     /// Processes the provided input based on the arguments contained within the struct.
     ///
     /// This function will consider the given arguments and convert the input to
@@ -35,51 +64,621 @@ impl Processing {
     /// let input_rust_code = "struct Example { field: i32 }";
     /// let output = processing.start(&input_rust_code);
     /// ```
-    pub fn start(&self, input: &String) -> HashMap<OutputFormat, String> {
-        let mut output_buffer = HashMap::new();
-        let markdown_output = process_input(input);
+    pub fn start(&mut self, input: &String) -> BTreeMap<OutputFormat, String> {
+        if self.args.input_kind == InputKind::Markdown {
+            let markdown_parser = MarkdownParser {
+                raw_markdown: input.clone(),
+            };
+            self.summary.count_class(&markdown_parser.parse_to_class());
+            return convert_markdown_input(input, &self.args);
+        }
+        let mut output_buffer = BTreeMap::new();
 
         if is_no_only_flag_set(&self.args) {
-            if self.args.format == OutputFormat::Markdown {
-                output_buffer.insert(OutputFormat::Markdown, markdown_output);
-            } else {
-                let ascii_doc_parser = AsciidocParser::new(None);
-                let mut asciidoc_output =
-                    match ascii_doc_parser.parse_from_markdown(&markdown_output) {
-                        Ok(asciidoc_string) => asciidoc_string,
-                        Err(e) => {
-                            panic!("Failed to parse markdown to asciidoc: '{}'", e);
-                        }
-                    };
-
-                if self.args.format == OutputFormat::AsciidocPlantuml {
-                    let plantuml_code = extract_plantuml_from_asciidoc(&asciidoc_output);
-                    output_buffer.insert(OutputFormat::AsciidocPlantuml, plantuml_code);
-                    asciidoc_output = replace_puml_with_include(&asciidoc_output);
-                }
+            let class = parse_input_to_class(input, &self.args, &mut self.diagnostics);
+            self.summary.count_class(&class);
+            let has_diagram = !class.plantuml.is_empty();
+            let extract_diagram = has_diagram && !is_inlined_diagram(&class.plantuml, &self.args);
+            let classes = [class];
+            // without a diagram, or with one staying inline, there is no separate
+            // PlantUML file to write
+            let format = match &self.args.format {
+                OutputFormat::AsciidocPlantuml if !extract_diagram => OutputFormat::Asciidoc,
+                format => format.clone(),
+            };
+            if format == OutputFormat::AsciidocPlantuml {
+                // The diagram stays embedded until the file it is written to is known,
+                // see `include_plantuml`.
+                let asciidoc_output =
+                    create_renderer(&OutputFormat::Asciidoc, &self.args).render(&classes);
+                let plantuml_code = extract_plantuml_from_asciidoc(&asciidoc_output);
+                output_buffer.insert(OutputFormat::AsciidocPlantuml, plantuml_code);
                 output_buffer.insert(OutputFormat::Asciidoc, asciidoc_output);
+            } else if format != OutputFormat::Plantuml || has_diagram {
+                let renderer = create_renderer(&format, &self.args);
+                output_buffer.insert(format, renderer.render(&classes));
             }
         } else {
-            output_buffer = process_input_only_flags(input, &self.args)
+            output_buffer = process_input_only_flags(input, &self.args, &mut self.diagnostics);
+            // the outputs of the only flags don't keep the class to count
+            self.summary.count_class(&self.reparse_class(input));
         };
 
         output_buffer
     }
+
+    /// Generates one PlantUML diagram per method of the input.
+    ///
+    /// Each diagram shows the class as in the class diagram, but with only the
+    /// respective method, which is highlighted in bold. This is meant for
+    /// documentation walking through an API method by method.
+    ///
+    /// # Returns
+    ///
+    /// The diagrams in the order of the methods in the input. None if the class
+    /// isn't drawn, like for several structs in one input.
+    pub fn per_method_diagrams(&self, input: &String) -> Vec<String> {
+        let class = self.reparse_class(input);
+        // the layered diagram doesn't show the members
+        let class_diagram_args = Cli {
+            layered_diagram: false,
+            ..self.args.clone()
+        };
+        let plantuml = parse_input_to_puml_string(input, &class_diagram_args);
+        let type_name = base_type_name(&class.name);
+
+        class
+            .methods
+            .iter()
+            .filter_map(|method| method_diagram(&plantuml, &type_name, &member_name(&method.name)))
+            .map(|diagram| with_preamble(diagram, &self.args))
+            .collect()
+    }
+
+    /// Renders the documentation of each method to a page of its own, named
+    /// `<Class>_<method>`, and an index page `<Class>_methods` linking to them.
+    ///
+    /// # Returns
+    ///
+    /// Pairs of file name and file content, the index being the last one.
+    pub fn split_methods(&self, input: &String) -> Vec<(String, String)> {
+        let class = self.reparse_class(input);
+        let extension = match self.args.format {
+            OutputFormat::Markdown => ".md",
+            _ => ".adoc",
+        };
+        let render = |markdown: &str| {
+            convert_markdown_input(markdown, &self.args)
+                .into_iter()
+                .find(|(format, _)| format != &OutputFormat::AsciidocPlantuml)
+                .map(|(_, content)| content)
+                .unwrap_or_default()
+        };
+
+        let mut pages = Vec::new();
+        let mut link_targets = Vec::new();
+        let type_name = base_type_name(&class.name);
+        let mut index = format!("## {} Methods\n\n", class.name);
+        for method in &class.methods {
+            let file_name = format!("{type_name}_{}{extension}", member_name(&method.name));
+            let markdown = format!(
+                "## {}::{}\n\n{}",
+                class.name, method.name, method.documentation
+            );
+            pages.push((file_name.clone(), render(&markdown)));
+            // the pages are written next to the index, with the same prefix
+            let link_target = if self.args.relative_links {
+                format!(
+                    "{}{file_name}",
+                    self.args.file_name_prefix.as_deref().unwrap_or_default()
+                )
+            } else {
+                file_name
+            };
+            index.push_str(&format!("- [{}]({link_target})\n", method.name));
+            link_targets.push(link_target);
+        }
+        let index_file_name = format!("{type_name}_methods{extension}");
+        let mut index = render(&index);
+        if self.args.relative_links && extension == ".adoc" {
+            index = use_xrefs(&index, &link_targets);
+        }
+        pages.push((index_file_name, index));
+        pages
+    }
+
+    /// Generates a component diagram of the modules of the input and their
+    /// dependencies.
+    pub fn component_diagram(&self, input: &str) -> String {
+        let parser = ComponentDiagramParser {
+            raw_rust_code: String::from(input),
+            include_tests: self.args.include_tests,
+        };
+        with_preamble(parser.parse_code_to_string(), &self.args)
+    }
+
+    /// Generates the class diagram of all types defined in the input, the module,
+    /// together with their relationships.
+    ///
+    /// # Returns
+    ///
+    /// The diagram, or `None` if the module defines no types.
+    pub fn module_diagram(&self, input: &String) -> Option<String> {
+        let plantuml = parse_input_to_puml_string(input, &self.args);
+        if is_empty_diagram(&plantuml) {
+            return None;
+        }
+        Some(with_preamble(plantuml, &self.args))
+    }
+
+    /// Extracts the code blocks of the example sections of the documentation into
+    /// runnable test files.
+    ///
+    /// Each Rust code block of a section whose heading contains 'Example' becomes
+    /// a test function of its own file. Hidden doctest lines starting with `# `
+    /// are included, blocks marked `ignore` or `no_run` carry that annotation as
+    /// comment and are ignored tests.
+    ///
+    /// # Returns
+    ///
+    /// Pairs of file name and file content in the order of the documentation.
+    pub fn extract_examples(&self, input: &String) -> Vec<(String, String)> {
+        let class = self.reparse_class(input);
+        let class_name = base_type_name(&class.name).to_lowercase();
+        let members = std::iter::once((base_type_name(&class.name), &class.documentation)).chain(
+            class
+                .fields
+                .iter()
+                .chain(&class.methods)
+                .map(|member| (member_name(&member.name), &member.documentation)),
+        );
+
+        let mut examples = Vec::new();
+        for (name, documentation) in members {
+            for (index, (code, annotations)) in collect_examples(documentation).iter().enumerate() {
+                let member = name.to_lowercase();
+                let test_name = if member == class_name {
+                    format!("{class_name}_{}", index + 1)
+                } else {
+                    format!("{class_name}_{member}_{}", index + 1)
+                };
+                let content = render_example_test(&test_name, code, annotations);
+                examples.push((format!("{test_name}.rs"), content));
+            }
+        }
+        examples
+    }
+
+    /// Collects the terms and definitions of the glossary sections of the
+    /// documentation into an AsciiDoc glossary table, like the glossary chapter
+    /// of arc42.
+    ///
+    /// A glossary section has the heading 'Glossary' and lists its terms as items
+    /// like `- Term: Definition`. The terms are sorted alphabetically. A term
+    /// defined more than once is warned about and keeps its first definition.
+    pub fn glossary(&mut self, input: &String) -> String {
+        let mut class = self.reparse_class(input);
+        let mut entries: Vec<(String, String)> = Vec::new();
+        let mut duplicates = Vec::new();
+        class.for_each_documentation(|documentation| {
+            for (term, definition) in collect_glossary_entries(documentation) {
+                if entries
+                    .iter()
+                    .any(|(known_term, _)| known_term.eq_ignore_ascii_case(&term))
+                {
+                    duplicates.push(term);
+                } else {
+                    entries.push((term, definition));
+                }
+            }
+        });
+        for term in duplicates {
+            self.diagnostics.warn(format!(
+                "Glossary term '{term}' is defined more than once, the first definition is kept"
+            ));
+        }
+        entries.sort_by_cached_key(|(term, _)| term.to_lowercase());
+        render_glossary(&entries)
+    }
+
+    /// Parses the input to a [Class] once more, e.g. for the additional outputs
+    /// next to the one of [Processing::start]. Its warnings were raised by the
+    /// first pass already, so they are dropped, and the diagram isn't generated again.
+    fn reparse_class(&self, input: &String) -> Class {
+        let args = Cli {
+            no_plantuml: true,
+            ..self.args.clone()
+        };
+        parse_input_to_class(input, &args, &mut Diagnostics::default())
+    }
+
+    /// Verifies that rendering the input to Markdown does not lose any members.
+    ///
+    /// The input is parsed to a [Class], rendered to Markdown and the Markdown is
+    /// parsed back again. Both classes are compared structurally by their names
+    /// and the names of their fields and methods.
+    ///
+    /// # Returns
+    ///
+    /// The differences found, formatted as a structured diff. The list is empty
+    /// if the round trip preserved the structure.
+    pub fn verify(&self, input: &String) -> Vec<String> {
+        let expected = self.reparse_class(input);
+        let markdown_parser = MarkdownParser {
+            raw_markdown: MarkdownRenderer::default().render(std::slice::from_ref(&expected)),
+        };
+        let actual = markdown_parser.parse_to_class();
+
+        let mut differences = Vec::new();
+        if expected.name != actual.name {
+            differences.push(format!(
+                "~ name: expected '{}', found '{}'",
+                expected.name, actual.name
+            ));
+        }
+        compare_members("field", &expected.fields, &actual.fields, &mut differences);
+        compare_members(
+            "method",
+            &expected.methods,
+            &actual.methods,
+            &mut differences,
+        );
+        compare_members(
+            "constant",
+            &expected.constants,
+            &actual.constants,
+            &mut differences,
+        );
+        differences
+    }
+}
+
+/// Processes a single job of the `--jobs-stdin` mode.
+///
+/// The job is read from a line of JSON and processed like a regular run with the
+/// given arguments, only the output format can be overridden per job. Invalid jobs
+/// and input which isn't valid Rust code are reported as error of the result.
+pub fn process_job(line: &str, args: &Cli) -> JobResult {
+    let job: Job = match serde_json::from_str(line) {
+        Ok(job) => job,
+        Err(e) => {
+            return JobResult {
+                error: Some(format!("Invalid job: {e}")),
+                ..Default::default()
+            }
+        }
+    };
+    if let Err(e) = syn::parse_file(&job.input) {
+        return JobResult {
+            error: Some(format!("Failed to parse input: {e}")),
+            ..Default::default()
+        };
+    }
+
+    let mut job_args = args.clone();
+    if let Some(format) = job.format {
+        job_args.format = format;
+    }
+    let mut processing = Processing::new(job_args);
+    let output = processing.start(&job.input);
+    JobResult {
+        output,
+        warnings: processing.diagnostics.warnings().to_vec(),
+        error: None,
+    }
+}
+
+/// Returns the name of a field or method without the parameter list.
+fn member_name(name: &str) -> String {
+    name.split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Returns the name of a type without its generic parameters, e.g. for file names.
+fn base_type_name(name: &str) -> String {
+    name.split('<')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Collects the Rust code blocks within the example sections of the documentation.
+///
+/// # Returns
+///
+/// Pairs of the code and the annotations of the code block, like `ignore`.
+fn collect_examples(documentation: &str) -> Vec<(String, Vec<String>)> {
+    let mut examples = Vec::new();
+    let mut in_example_section = false;
+    // The code block currently read, `None` for blocks which aren't collected.
+    let mut code_block: Option<Option<(String, Vec<String>)>> = None;
+
+    for line in documentation.lines() {
+        let trimmed = line.trim_start();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            match code_block.take() {
+                Some(example) => examples.extend(example),
+                None => {
+                    let annotations = parse_code_block_annotations(info);
+                    let is_collected = in_example_section && annotations.is_some();
+                    code_block = Some(
+                        annotations
+                            .filter(|_| is_collected)
+                            .map(|annotations| (String::new(), annotations)),
+                    );
+                }
+            }
+            continue;
+        }
+        match code_block.as_mut() {
+            Some(Some((code, _))) => {
+                // Hidden doctest lines are part of the runnable example.
+                let line = line.strip_prefix("# ").unwrap_or(line);
+                code.push_str(if line == "#" { "" } else { line });
+                code.push('\n');
+            }
+            Some(None) => {}
+            None if trimmed.starts_with('#') => {
+                in_example_section = trimmed.contains("Example");
+            }
+            None => {}
+        }
+    }
+    examples
+}
+
+/// Collects the `- Term: Definition` items of the glossary sections of the
+/// documentation. Lines following an item continue its definition.
+fn collect_glossary_entries(documentation: &str) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut in_glossary_section = false;
+    let mut in_code_block = false;
+    for line in documentation.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            in_glossary_section = trimmed.trim_start_matches('#').trim() == "Glossary";
+            continue;
+        }
+        if !in_glossary_section || trimmed.is_empty() {
+            continue;
+        }
+        let item = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+            .and_then(|item| item.split_once(": "));
+        match (item, entries.last_mut()) {
+            (Some((term, definition)), _) => {
+                let term = term
+                    .trim()
+                    .trim_matches(|character| character == '*' || character == '`');
+                entries.push((term.to_string(), definition.trim().to_string()));
+            }
+            (None, Some((_, definition))) => {
+                definition.push(' ');
+                definition.push_str(trimmed);
+            }
+            (None, None) => {}
+        }
+    }
+    entries
+}
+
+/// Renders the glossary as AsciiDoc table with a column for the terms and a
+/// wider one for the definitions.
+fn render_glossary(entries: &[(String, String)]) -> String {
+    let escape = |text: &str| text.replace('|', "\\|");
+    let mut glossary = String::from("= Glossary\n\n[cols=\"1,3\",options=\"header\"]\n|===\n");
+    glossary.push_str("|Term |Definition\n");
+    for (term, definition) in entries {
+        glossary.push_str(&format!("\n|{}\n|{}\n", escape(term), escape(definition)));
+    }
+    glossary.push_str("|===\n");
+    glossary
+}
+
+/// Parses the annotations of a code block like `rust,ignore`.
+///
+/// Returns `None` if the code block isn't Rust code, e.g. `text`.
+fn parse_code_block_annotations(info: &str) -> Option<Vec<String>> {
+    let annotations: Vec<String> = info
+        .split(',')
+        .map(|annotation| annotation.trim().to_string())
+        .filter(|annotation| !annotation.is_empty() && annotation != "rust")
+        .collect();
+    let is_rust = annotations.iter().all(|annotation| {
+        matches!(
+            annotation.as_str(),
+            "ignore" | "no_run" | "should_panic" | "edition2018" | "edition2021"
+        )
+    });
+    is_rust.then_some(annotations)
+}
+
+/// Renders an example as test function, ignored if marked `ignore` or `no_run`.
+fn render_example_test(test_name: &str, code: &str, annotations: &[String]) -> String {
+    let mut test = String::new();
+    for annotation in annotations {
+        test.push_str(&format!("// {annotation}\n"));
+    }
+    test.push_str("#[test]\n");
+    if annotations
+        .iter()
+        .any(|annotation| annotation == "ignore" || annotation == "no_run")
+    {
+        test.push_str("#[ignore]\n");
+    }
+    if annotations
+        .iter()
+        .any(|annotation| annotation == "should_panic")
+    {
+        test.push_str("#[should_panic]\n");
+    }
+    test.push_str(&format!("fn {test_name}() {{\n"));
+    for line in code.lines() {
+        if line.is_empty() {
+            test.push('\n');
+        } else {
+            test.push_str(&format!("    {line}\n"));
+        }
+    }
+    test.push_str("}\n");
+    test
+}
+
+/// Compares the members of two classes by name and records all differences.
+fn compare_members(
+    kind: &str,
+    expected: &[Method],
+    actual: &[Method],
+    differences: &mut Vec<String>,
+) {
+    if expected.len() != actual.len() {
+        differences.push(format!(
+            "~ {kind}s: expected {}, found {}",
+            expected.len(),
+            actual.len()
+        ));
+    }
+    for member in expected {
+        if !actual.iter().any(|other| other.name == member.name) {
+            differences.push(format!("- missing {kind} '{}'", member.name));
+        }
+    }
+    for member in actual {
+        if !expected.iter().any(|other| other.name == member.name) {
+            differences.push(format!("+ unexpected {kind} '{}'", member.name));
+        }
+    }
+}
+
+/// Creates the renderer for the given output format, configured by the arguments.
+///
+/// The combined 'asciidoc-plantuml' format is rendered as AsciiDoc, the PlantUML
+/// is split off afterwards.
+fn create_renderer(format: &OutputFormat, args: &Cli) -> Box<dyn Renderer> {
+    match format {
+        OutputFormat::Asciidoc | OutputFormat::AsciidocPlantuml => Box::new(AsciidocRenderer {
+            title: args.title.clone(),
+            author: args.author.clone(),
+            revdate: args.revdate.clone(),
+            toc: args.toc,
+            markdown_renderer: MarkdownRenderer {
+                method_anchors: args.method_anchors,
+                definition_lists: args.definition_lists,
+                ..create_markdown_renderer(args)
+            },
+            pandoc_timeout: Some(Duration::from_secs(args.pandoc_timeout)),
+            pandoc_retry: args.pandoc_retry,
+            verbatim_examples: args.verbatim_examples,
+            highlight_asserts: args.highlight_asserts,
+        }),
+        OutputFormat::Markdown => Box::new(MarkdownRenderer {
+            toc: args.toc,
+            ..create_markdown_renderer(args)
+        }),
+        OutputFormat::Plantuml => Box::new(PlantumlRenderer::default()),
+        OutputFormat::Json => Box::new(JsonRenderer::default()),
+        OutputFormat::Toml => Box::new(TomlRenderer::default()),
+    }
+}
+
+/// Creates the Markdown renderer, which is also the base of the AsciiDoc output.
+fn create_markdown_renderer(args: &Cli) -> MarkdownRenderer {
+    MarkdownRenderer {
+        diagram_fence: args.diagram_fence.clone(),
+        headings: args.heading_config.clone().unwrap_or_default(),
+        group_by_visibility: args.group_by_visibility,
+        group_builders: args.group_builders,
+        unified_members: args.unified_members,
+        ..Default::default()
+    }
+}
+
+/// Converts input which already is Markdown to the output format, without parsing
+/// Rust code. Only the Markdown and AsciiDoc formats can be generated from it.
+fn convert_markdown_input(input: &str, args: &Cli) -> BTreeMap<OutputFormat, String> {
+    let mut output_buffer = BTreeMap::new();
+    match args.format {
+        OutputFormat::Markdown => {
+            output_buffer.insert(OutputFormat::Markdown, input.to_string());
+        }
+        OutputFormat::Asciidoc | OutputFormat::AsciidocPlantuml => {
+            let asciidoc = AsciidocParser::new(None)
+                .with_diagram_fence(&args.diagram_fence)
+                .with_timeout(Duration::from_secs(args.pandoc_timeout))
+                .with_retry(args.pandoc_retry)
+                .with_verbatim_examples(args.verbatim_examples)
+                .with_highlight_asserts(args.highlight_asserts)
+                .parse_from_markdown(input)
+                .unwrap_or_else(|e| panic!("Failed to parse markdown to asciidoc: '{}'", e));
+            if args.format == OutputFormat::AsciidocPlantuml && asciidoc.contains("@startuml") {
+                let plantuml_code = extract_plantuml_from_asciidoc(&asciidoc);
+                if !is_inlined_diagram(&plantuml_code, args) {
+                    output_buffer.insert(OutputFormat::AsciidocPlantuml, plantuml_code);
+                }
+            }
+            output_buffer.insert(OutputFormat::Asciidoc, asciidoc);
+        }
+        OutputFormat::Plantuml | OutputFormat::Json | OutputFormat::Toml => {
+            panic!(
+                "The {:?} format can't be generated from Markdown input",
+                args.format
+            )
+        }
+    }
+    output_buffer
+}
+
+/// Returns true if the diagram is small enough to stay embedded in the AsciiDoc,
+/// see '--inline-diagrams'.
+fn is_inlined_diagram(plantuml: &str, args: &Cli) -> bool {
+    args.inline_diagrams
+        .is_some_and(|max_lines| plantuml.lines().count() <= max_lines)
 }
 
 /// Replaces the PlantUML content within an AsciiDoc string with an include directive.
 /// The embedded PlantUML content will be replaced with the following include directive:
-/// `plantuml::FILENAME.puml[]`
-/// So later on the 'FILENAME' can be replaced with the actual file name of the PUML file.
-fn replace_puml_with_include(asciidoc_string: &str) -> String {
-    let replacement = "plantuml::FILENAME.puml[]";
+/// `plantuml::<include_target>[]`
+///
+/// # Arguments
+/// * `asciidoc_string` - The AsciiDoc with the embedded PlantUML.
+/// * `include_target` - The path of the PUML file.
+pub fn include_plantuml(asciidoc_string: &str, include_target: &str) -> String {
+    let replacement = format!("plantuml::{include_target}[]");
     // This regex will be more flexible in capturing potential whitespace variations.
     let pattern = r"(?s)\[plantuml\][\n\r]+----[\n\r]+.*?@enduml[\n\r]+----";
     let regex = Regex::new(pattern).unwrap();
-    let new_string = regex.replace_all(asciidoc_string, replacement).to_string();
+    let new_string = regex
+        .replace_all(asciidoc_string, regex::NoExpand(&replacement))
+        .to_string();
     new_string
 }
 
+/// Strips the trailing whitespace of every line, including the `\r` of Windows
+/// line endings, and ensures the text ends with exactly one newline.
+pub fn normalize_whitespace(text: &str) -> String {
+    let mut normalized = text
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim_end_matches('\n')
+        .to_string();
+    normalized.push('\n');
+    normalized
+}
+
 /// Retrieves the string content located within the plantuml section.
 fn extract_plantuml_from_asciidoc(asciidoc_output: &str) -> String {
     let start_tag = "@startuml";
@@ -93,78 +692,325 @@ fn extract_plantuml_from_asciidoc(asciidoc_output: &str) -> String {
     lines.add(format!("\n{end_tag}\n").as_str())
 }
 
+/// Returns true if the diagram has nothing between `@startuml` and `@enduml`.
+fn is_empty_diagram(plantuml: &str) -> bool {
+    plantuml
+        .trim()
+        .trim_start_matches("@startuml")
+        .trim_end_matches("@enduml")
+        .trim()
+        .is_empty()
+}
+
 /// Processes the input content and generates the output content based on the provided only flags.
+/// With both flags set, the PlantUML and the Markdown are generated.
 ///
 /// # Returns
 /// A mapping from the desired output format to the corresponding processed string.
-/// The output content as a [HashMap] where key is [OutputFormat] and value is [String].
-fn process_input_only_flags(input: &String, args: &Cli) -> HashMap<OutputFormat, String> {
-    let mut output_buffer = HashMap::new();
+/// The output content as a [BTreeMap] where key is [OutputFormat] and value is [String].
+fn process_input_only_flags(
+    input: &String,
+    args: &Cli,
+    diagnostics: &mut Diagnostics,
+) -> BTreeMap<OutputFormat, String> {
+    let mut output_buffer = BTreeMap::new();
 
     if args.only_flags.plantuml_only {
-        let plantuml_string = parse_input_to_puml_string(input);
-        output_buffer.insert(OutputFormat::Plantuml, plantuml_string);
-    } else if args.only_flags.markdown_only {
-        let markdown_string = parse_input_to_markdown_string(input);
-        output_buffer.insert(OutputFormat::Markdown, markdown_string);
+        let plantuml_string = parse_input_to_puml_string(input, args);
+        let is_empty = is_empty_diagram(&plantuml_string);
+        let plantuml_string = with_preamble(plantuml_string, args);
+        validate_plantuml(&plantuml_string, args, diagnostics);
+        if !(args.emit_plantuml_only_when_nonempty && is_empty) {
+            output_buffer.insert(OutputFormat::Plantuml, plantuml_string);
+        }
+    }
+    if args.only_flags.markdown_only {
+        // the diagram isn't part of the Markdown-only output
+        let markdown_args = Cli {
+            no_plantuml: true,
+            ..args.clone()
+        };
+        let class = parse_input_to_class(input, &markdown_args, diagnostics);
+        let renderer = MarkdownRenderer {
+            toc: args.toc,
+            ..create_markdown_renderer(args)
+        };
+        output_buffer.insert(OutputFormat::Markdown, renderer.render(&[class]));
     }
 
     output_buffer
 }
 
+/// Checks the diagram with the syntax check of the `plantuml` executable, if
+/// requested. A syntax error, or an executable which can't be run, is a warning,
+/// which fails the run in strict mode.
+fn validate_plantuml(plantuml: &str, args: &Cli, diagnostics: &mut Diagnostics) {
+    if !args.validate_plantuml || is_empty_diagram(plantuml) {
+        return;
+    }
+    let validator =
+        PlantumlValidator::new(None).with_timeout(Duration::from_secs(args.plantuml_timeout));
+    match validator.syntax_error(plantuml) {
+        Ok(None) => {}
+        Ok(Some(error)) => diagnostics.warn(format!("The generated PlantUML is invalid, {error}")),
+        Err(e) => diagnostics.warn(format!(
+            "Skipped the validation of the PlantUML, 'plantuml' could not be run: {e}"
+        )),
+    }
+}
+
+/// Inserts the content of the preamble file into the diagram, if given.
+fn with_preamble(plantuml: String, args: &Cli) -> String {
+    match &args.preamble_file {
+        Some(preamble) => insert_preamble(&plantuml, preamble),
+        None => plantuml,
+    }
+}
+
 /// Parses the input Rust code to a PlantUML string representation.
-fn parse_input_to_puml_string(input: &String) -> String {
+///
+/// If the `layered_diagram` flag is set, a layered overview diagram is generated
+/// instead of the class diagram.
+fn parse_input_to_puml_string(input: &String, args: &Cli) -> String {
+    if args.layered_diagram {
+        let layered_diagram_parser = LayeredDiagramParser {
+            raw_rust_code: String::from(input),
+            include_tests: args.include_tests,
+        };
+        return layered_diagram_parser.parse_code_to_string();
+    }
     let plantuml_parser = PlantumlParser {
         raw_rust_code: String::from(input),
+        stereotype_from: args.stereotype_from.clone(),
+        bound_arrows: args.bound_arrows,
+        exclude_std_bounds: args.exclude_std_bounds,
+        include_tests: args.include_tests,
+        struct_keyword: args.plantuml_struct_keyword.clone(),
+        group_by_visibility: args.group_by_visibility,
+        field_notes: args.field_notes,
+        abbreviate_types: args.abbreviate_types,
+        namespace: args.plantuml_namespace.clone(),
+        legend: args.plantuml_legend,
+        package_by_module: args.package_by_module,
+        const_generics_note: args.const_generics_note,
     };
     plantuml_parser.parse_code_to_string()
 }
 
-/// Parses Rust documentation from the input code to a Markdown string representation.
-fn parse_input_to_markdown_string(input: &String) -> String {
-    let markdown_parser = RustDocParser {
+/// Parses the input Rust code to a [Class] including its PlantUML representation,
+/// unless the PlantUML generation is disabled.
+fn parse_input_to_class(input: &String, args: &Cli, diagnostics: &mut Diagnostics) -> Class {
+    let doc_parser = RustDocParser {
         raw_rust_code: String::from(input),
+        show_defaults: args.show_defaults,
+        document_returns: args.document_returns,
+        document_private_items: args.document_private_items,
+        embed_source: args.embed_source,
+        exclude_attributes: args.exclude_attr.clone(),
+        canonical_paths: args.canonical_paths,
+        show_attributes: args.show_attributes,
+        merge_impls_into_declaration_order: args.merge_impls_into_declaration_order,
+        document_derived_impls: args.document_derived_impls,
+        include_tests: args.include_tests,
     };
-    markdown_parser.parse_code_doc_to_markdown_string()
+
+    let mut class = doc_parser.parse_code_doc_with_diagnostics(diagnostics);
+    if !args.no_plantuml {
+        let plantuml = parse_input_to_puml_string(input, args);
+        // the excluded methods are only left out of the documentation
+        if !args.layered_diagram && args.exclude_attr.is_empty() {
+            check_diagram_methods(&class, &plantuml, diagnostics);
+        }
+        let is_empty = is_empty_diagram(&plantuml);
+        // the diagram is checked as it is written, including the preamble
+        let plantuml = with_preamble(plantuml, args);
+        validate_plantuml(&plantuml, args, diagnostics);
+        if !(args.emit_plantuml_only_when_nonempty && is_empty) {
+            class.plantuml = plantuml;
+        }
+    }
+    if let Some(methods_regex) = &args.methods_regex {
+        filter_methods_by_name(&mut class, methods_regex);
+    }
+    sort_members(&mut class.fields, &args.sort_fields);
+    sort_members(&mut class.methods, &args.sort_methods);
+    if let Some(since_regex) = &args.since {
+        add_since_markers(&mut class, since_regex);
+    }
+    if args.summary_only {
+        class.for_each_documentation(|documentation| {
+            *documentation = summarize(documentation);
+        });
+    }
+    if let Some(missing_doc_text) = &args.missing_doc_text {
+        fill_missing_documentation(&mut class, missing_doc_text);
+    }
+    if args.strip_private_doc {
+        strip_private_documentation(&mut class);
+    }
+    class
 }
 
-/// Processes the input when no `only` flag is set in the provided CLI arguments.
+/// Warns about methods the documentation and the diagram disagree on, as they
+/// are collected by different parsers.
 ///
-/// # Arguments
-/// * `input` - The Rust code string to be processed.
-///
-/// # Returns
-/// The processed content as a single string.
-fn process_input(input: &String) -> String {
-    let mut output_buffer = String::new();
-    let plantuml_parser = PlantumlParser {
-        raw_rust_code: String::from(input),
-    };
-    let doc_parser = RustDocParser {
-        raw_rust_code: String::from(input),
+/// Each documented method has to be in the diagram, and each public method of the
+/// diagram has to be documented, either as method or in a trait implementation.
+/// Nothing is checked if the diagram has no type named like the class.
+fn check_diagram_methods(class: &Class, plantuml: &str, diagnostics: &mut Diagnostics) {
+    let method_name = |method: &Method| member_name(&method.name);
+    let documented: Vec<String> = class.methods.iter().map(method_name).collect();
+    let trait_methods: Vec<String> = class
+        .trait_impls
+        .iter()
+        .flat_map(|trait_impl| trait_impl.methods.iter().map(method_name))
+        .collect();
+    // several structs in one input are documented as one class, which isn't drawn
+    let Some(diagram) = diagram_methods(plantuml, &base_type_name(&class.name)) else {
+        return;
     };
 
-    let plantuml = plantuml_parser.parse_code_to_string();
-    let mut documentation = doc_parser.parse_code_doc();
-    documentation.plantuml = plantuml;
+    for name in &documented {
+        if !diagram.iter().any(|(_, diagram_name)| diagram_name == name) {
+            diagnostics.warn(format!(
+                "Method '{name}' of '{}' is documented, but missing in the diagram",
+                class.name
+            ));
+        }
+    }
+    for (_, name) in diagram.iter().filter(|(symbol, _)| *symbol == '+') {
+        if !documented.contains(name) && !trait_methods.contains(name) {
+            diagnostics.warn(format!(
+                "Method '{name}' of '{}' is in the diagram, but missing in the documentation",
+                class.name
+            ));
+        }
+    }
+}
+
+/// Puts a 'Since' marker in front of the documentation of the class and each of
+/// its members, if the documentation names the version the item was introduced in.
+///
+/// The first capture group of the regular expression is the version.
+fn add_since_markers(class: &mut Class, since_regex: &str) {
+    let regex = fancy_regex::Regex::new(since_regex).expect("Invalid since regex");
+    class.for_each_documentation(|documentation| {
+        let version = regex
+            .captures(documentation)
+            .ok()
+            .flatten()
+            .and_then(|captures| captures.get(1))
+            .map(|version| version.as_str().to_string());
+        if let Some(version) = version {
+            documentation.insert_str(0, &format!("*Since {version}*\n\n"));
+        }
+    });
+}
+
+/// Returns the block of the link reference and footnote definitions at the end
+/// of the documentation, where the parser gathers them, including the indented
+/// continuation lines of the footnotes.
+fn trailing_reference_definitions(documentation: &str) -> Option<&str> {
+    let (_, block) = documentation.trim_end().rsplit_once("\n\n")?;
+    block
+        .lines()
+        .all(|line| is_reference_definition(line) || line.starts_with("    "))
+        .then_some(block)
+}
+
+/// Shortens the documentation to its summary, the first paragraph, which ends
+/// at an empty line, a heading or a code block. A since marker in front of it is
+/// kept, as are the link reference definitions, so the links of the summary
+/// still resolve.
+fn summarize(documentation: &str) -> String {
+    let mut summary = String::new();
+    let mut lines = documentation
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .peekable();
+    if let Some(marker) = lines.next_if(|line| line.starts_with("*Since ")) {
+        summary.push_str(&format!("{marker}\n\n"));
+        while lines.next_if(|line| line.trim().is_empty()).is_some() {}
+    }
+    for line in lines {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("```") {
+            break;
+        }
+        summary.push_str(line);
+        summary.push('\n');
+    }
+    if let Some(definitions) = trailing_reference_definitions(documentation) {
+        summary.push_str(&format!("\n{definitions}\n"));
+    }
+    summary
+}
 
-    output_buffer.push_str(format!("## {}\n", documentation.name).as_str());
-    output_buffer.push_str(format!("```plantuml\n{}\n```\n", documentation.plantuml).as_str());
-    output_buffer.push_str(format!("\n{}\n", documentation.documentation).as_str());
+/// Replaces the empty documentation of the class and its members by the text,
+/// so undocumented items can be told apart from broken output.
+fn fill_missing_documentation(class: &mut Class, missing_doc_text: &str) {
+    class.for_each_documentation(|documentation| {
+        if documentation.trim().is_empty() {
+            *documentation = format!("{missing_doc_text}\n");
+        }
+    });
+}
 
-    //output all fields with its documentation in an markdown list
-    for field in documentation.fields {
-        output_buffer.push_str(format!("\n### {}\n", field.name).as_str());
-        output_buffer.push_str(format!("{}\n", field.documentation).as_str());
+/// Clears the documentation of the members which aren't `pub`, which stay listed
+/// with their signature. Members with unknown visibility, like those of trait
+/// implementations, keep their documentation.
+fn strip_private_documentation(class: &mut Class) {
+    let foreign_items = class
+        .foreign_mods
+        .iter_mut()
+        .flat_map(|foreign_mod| foreign_mod.items.iter_mut());
+    for member in class
+        .fields
+        .iter_mut()
+        .chain(&mut class.methods)
+        .chain(&mut class.constants)
+        .chain(&mut class.reexports)
+        .chain(foreign_items)
+    {
+        if member
+            .visibility
+            .as_ref()
+            .is_some_and(|visibility| visibility != "pub")
+        {
+            member.documentation.clear();
+        }
     }
+}
+
+/// Removes all methods from the class whose name does not match the regular expression.
+/// Only the name itself is matched, without the parameter list.
+fn filter_methods_by_name(class: &mut Class, methods_regex: &str) {
+    let regex = fancy_regex::Regex::new(methods_regex).expect("Invalid methods regex");
+    class.methods.retain(|method| {
+        let name = method.name.split('(').next().unwrap_or_default();
+        regex.is_match(name).unwrap_or(false)
+    });
+}
 
-    //output each method with its documentation in an markdown list
-    for method in documentation.methods {
-        output_buffer.push_str(format!("\n### {}\n", method.name).as_str());
-        output_buffer.push_str(format!("{}\n", method.documentation).as_str());
+/// Sorts the members in the given order. Members with the same name, like
+/// overloads of different impl blocks, keep their source order.
+fn sort_members(members: &mut [Method], order: &SortOrder) {
+    if *order == SortOrder::Alphabetical {
+        members.sort_by_cached_key(|member| {
+            let name = member.name.split('(').next().unwrap_or_default();
+            name.trim().to_lowercase()
+        });
     }
+}
 
-    output_buffer
+/// Replaces the `link:` macros to the given AsciiDoc documents by `xref:` macros,
+/// which Asciidoctor resolves to the rendered documents, e.g. the `.html` files.
+fn use_xrefs(asciidoc: &str, targets: &[String]) -> String {
+    targets
+        .iter()
+        .fold(asciidoc.to_string(), |asciidoc, target| {
+            asciidoc.replace(&format!("link:{target}["), &format!("xref:{target}["))
+        })
 }
 
 /// Returns true if no `only` flag is set.
@@ -181,7 +1027,7 @@ mod tests {
     use std::fs;
     use std::io::Read;
 
-    use crate::cli::OnlyFlags;
+    use crate::cli::{ColorChoice, InputCharset, OnlyFlags, StructKeyword};
 
     use super::*;
 
@@ -202,7 +1048,85 @@ mod tests {
             output_file,
             format,
             preserve_names: false,
+            input_stdin_name: None,
             file_name_prefix: Some(String::new()),
+            name_pattern: String::from("{stem}{ext}"),
+            output_dir: None,
+            relative_includes: false,
+            base_dir: None,
+            toc: false,
+            stereotype_from: None,
+            layered_diagram: false,
+            print_schema: false,
+            verify: false,
+            per_method_diagrams: false,
+            methods_regex: None,
+            title: None,
+            author: None,
+            revdate: None,
+            diff: None,
+            strict: false,
+            show_defaults: false,
+            jobs_stdin: false,
+            lib: false,
+            bin: None,
+            document_returns: false,
+            bound_arrows: false,
+            exclude_std_bounds: false,
+            document_private_items: true,
+            embed_source: false,
+            extract_examples: None,
+            color: ColorChoice::Never,
+            include_tests: false,
+            diagram_fence: String::from("plantuml"),
+            normalize_whitespace: false,
+            no_plantuml: false,
+            since: None,
+            exclude_attr: Vec::new(),
+            input_kind: InputKind::Rust,
+            method_anchors: false,
+            component_diagram: None,
+            missing_doc_text: None,
+            split_methods: false,
+            relative_links: false,
+            plantuml_struct_keyword: StructKeyword::Class,
+            pandoc_timeout: 30,
+            pandoc_retry: false,
+            group_by_visibility: false,
+            field_notes: None,
+            module_tree: None,
+            emit_plantuml_only_when_nonempty: false,
+            abbreviate_types: None,
+            canonical_paths: false,
+            heading_config: None,
+            show_attributes: false,
+            preamble_file: None,
+            group_builders: false,
+            inline_diagrams: None,
+            verbatim_examples: false,
+            sort_fields: SortOrder::Source,
+            sort_methods: SortOrder::Source,
+            input_charset: InputCharset::Utf8,
+            glossary: None,
+            keep_going: false,
+            plantuml_namespace: None,
+            summary_only: false,
+            unified_members: false,
+            quiet: false,
+            plantuml_legend: false,
+            module_diagrams: false,
+            merge_impls_into_declaration_order: false,
+            highlight_asserts: false,
+            validate_plantuml: false,
+            plantuml_timeout: 30,
+            package_by_module: false,
+            strip_private_doc: false,
+            definition_lists: false,
+            const_generics_note: false,
+            validate_includes: false,
+            output_format_from_extension: false,
+            files_from: None,
+            document_derived_impls: false,
         }
     }
 
@@ -219,7 +1143,7 @@ mod tests {
         let expected_content = "@startuml";
         let not_expected_content = "## ";
 
-        let processing = Processing { args: cli_mock };
+        let mut processing = Processing::new(cli_mock);
         let output = processing.start(&raw_rust_code);
 
         let expected_output_format = &OutputFormat::Plantuml;
@@ -248,7 +1172,7 @@ mod tests {
         let expected_content = "## ";
         let not_expected_content = "@startuml";
 
-        let processing = Processing { args: cli_mock };
+        let mut processing = Processing::new(cli_mock);
         let output = processing.start(&raw_rust_code);
 
         let expected_output_format = &OutputFormat::Markdown;
@@ -264,6 +1188,81 @@ mod tests {
             .contains(not_expected_content));
     }
 
+    #[test]
+    fn only_flag_markdown_uses_the_documentation_options() {
+        let mut cli_mock = create_mock_cli(None, None, false, true, OutputFormat::Asciidoc);
+        cli_mock.document_private_items = true;
+        cli_mock.missing_doc_text = Some(String::from("_Undocumented._"));
+        let raw_rust_code = String::from(
+            r#"
+            struct TestStruct {
+                /// The private field.
+                field: String,
+                other: u32,
+            }
+            "#,
+        );
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        let markdown = output.get(&OutputFormat::Markdown).unwrap();
+        assert!(markdown.contains("### field\nThe private field.\n"));
+        assert!(markdown.contains("### other\n_Undocumented._\n"));
+    }
+
+    #[test]
+    fn only_flags_plantuml_and_markdown() {
+        let cli_mock = create_mock_cli(None, None, true, true, OutputFormat::Asciidoc);
+        let raw_rust_code = String::from(
+            r#"
+            struct TestStruct {
+                field: String,
+            }
+            "#,
+        );
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        assert_eq!(output.len(), 2);
+        assert!(output
+            .get(&OutputFormat::Plantuml)
+            .unwrap()
+            .contains("@startuml"));
+        assert!(output.get(&OutputFormat::Markdown).unwrap().contains("## "));
+        assert!(!output.contains_key(&OutputFormat::Asciidoc));
+    }
+
+    #[test]
+    fn only_flag_plantuml_counts_the_class() {
+        let cli_mock = create_mock_cli(None, None, true, false, OutputFormat::Asciidoc);
+        let raw_rust_code = String::from("struct TestStruct { field: String }");
+
+        let mut processing = Processing::new(cli_mock);
+        processing.start(&raw_rust_code);
+
+        assert_eq!(processing.summary.types, 1);
+        assert_eq!(processing.summary.fields, 1);
+    }
+
+    #[test]
+    fn test_module_diagram_shows_all_types_of_the_module() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        let processing = Processing::new(cli_mock);
+
+        let diagram = processing
+            .module_diagram(&String::from(
+                "struct Order { id: u32 }\nstruct Customer { id: u32 }",
+            ))
+            .unwrap();
+        let no_types_diagram = processing.module_diagram(&String::from("const LIMIT: u32 = 10;"));
+
+        assert!(diagram.contains("class \"Order\" {"));
+        assert!(diagram.contains("class \"Customer\" {"));
+        assert!(no_types_diagram.is_none());
+    }
+
     #[test]
     fn test_process_input() {
         let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Asciidoc);
@@ -279,7 +1278,7 @@ mod tests {
         let expected_headline = " Person";
         let expected_plantuml = "class \"Person\"";
 
-        let processing = Processing { args: cli_mock };
+        let mut processing = Processing::new(cli_mock);
         let output = processing.start(&raw_rust_code);
 
         let expected_output_format = &OutputFormat::Asciidoc;
@@ -301,7 +1300,7 @@ mod tests {
         let raw_rust_code = String::from("struct Person { name: String }");
         let expected_headline = "## Person";
 
-        let processing = Processing { args: cli_mock };
+        let mut processing = Processing::new(cli_mock);
         let output = processing.start(&raw_rust_code);
 
         let expected_output_format = &OutputFormat::Markdown;
@@ -314,23 +1313,781 @@ mod tests {
     }
 
     #[test]
-    fn test_process_input_format_asciidoc() {
-        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Asciidoc);
+    fn test_process_input_format_json() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Json);
         let raw_rust_code = String::from("struct Person { name: String }");
-        let expected_headline = "== Person";
+        let expected_name = "\"name\": \"Person\"";
 
-        let processing = Processing { args: cli_mock };
+        let mut processing = Processing::new(cli_mock);
         let output = processing.start(&raw_rust_code);
 
-        let expected_output_format = &OutputFormat::Asciidoc;
+        let expected_output_format = &OutputFormat::Json;
         assert!(output.contains_key(expected_output_format));
-        assert!(output.get(expected_output_format).is_some());
+        assert!(output
+            .get(expected_output_format)
+            .unwrap()
+            .contains(expected_name));
+    }
+
+    #[test]
+    fn test_process_job_overrides_format() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Asciidoc);
+        let line = r#"{"input": "struct Person { name: String }", "format": "markdown"}"#;
+
+        let result = process_job(line, &cli_mock);
+
+        assert!(result.error.is_none());
+        assert!(result.output[&OutputFormat::Markdown].contains("## Person"));
+        assert_eq!(
+            serde_json::to_string(&result).unwrap(),
+            format!(
+                "{{\"output\":{{\"markdown\":{}}}}}",
+                serde_json::to_string(&result.output[&OutputFormat::Markdown]).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_process_job_reports_errors() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+
+        let invalid_job = process_job("not json", &cli_mock);
+        let invalid_input = process_job(r#"{"input": "struct {"}"#, &cli_mock);
+
+        assert!(invalid_job.error.unwrap().starts_with("Invalid job"));
+        assert!(invalid_input.output.is_empty());
+        assert!(invalid_input
+            .error
+            .unwrap()
+            .starts_with("Failed to parse input"));
+    }
+
+    #[test]
+    fn test_methods_regex_filters_methods_by_name() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        cli_mock.methods_regex = Some(String::from("^(?!internal_)"));
+        let raw_rust_code = String::from(
+            r#"
+            struct Worker {}
+            impl Worker {
+                pub fn do_thing(&self, internal_state: u32) {}
+                fn internal_helper(&self) {}
+            }
+            "#,
+        );
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        let markdown = output.get(&OutputFormat::Markdown).unwrap();
+        assert!(markdown.contains("### do_thing(internal_state: u32)"));
+        assert!(!markdown.contains("### internal_helper"));
+    }
+
+    #[test]
+    fn test_glossary_collects_sorted_terms_and_warns_about_duplicates() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        let raw_rust_code = String::from(
+            r#"
+            /// An axle counter.
+            ///
+            /// # Glossary
+            /// - Track section: A part of the track, whose occupancy is
+            ///   detected.
+            /// - **Axle**: A pair of wheels.
+            pub struct Counter {}
+            impl Counter {
+                /// Counts an axle.
+                ///
+                /// # Glossary
+                /// - axle: Another definition.
+                /// - `Sensor|Head`: Detects the wheels.
+                pub fn count(&mut self) {}
+            }
+            "#,
+        );
+
+        let mut processing = Processing::new(cli_mock);
+        let glossary = processing.glossary(&raw_rust_code);
+
+        assert_eq!(
+            glossary,
+            "= Glossary\n\n[cols=\"1,3\",options=\"header\"]\n|===\n|Term |Definition\n\
+            \n|Axle\n|A pair of wheels.\n\
+            \n|Sensor\\|Head\n|Detects the wheels.\n\
+            \n|Track section\n|A part of the track, whose occupancy is detected.\n\
+            |===\n"
+        );
+        assert_eq!(
+            processing.diagnostics.warnings(),
+            ["Glossary term 'axle' is defined more than once, the first definition is kept"]
+        );
+    }
+
+    #[test]
+    fn test_json_output_has_a_stable_member_order() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Json);
+        cli_mock.sort_fields = SortOrder::Alphabetical;
+        cli_mock.sort_methods = SortOrder::Alphabetical;
+        let raw_rust_code = String::from(
+            r#"
+            pub struct Point {
+                pub y: i32,
+                pub x: i32,
+            }
+            impl Point {
+                pub fn scale(&self) {}
+                pub fn length(&self) {}
+            }
+            "#,
+        );
+
+        let first = Processing::new(cli_mock.clone()).start(&raw_rust_code);
+        let second = Processing::new(cli_mock).start(&raw_rust_code);
+
+        let json = first.get(&OutputFormat::Json).unwrap();
+        assert_eq!(json, second.get(&OutputFormat::Json).unwrap());
+        let position = |name: &str| json.find(&format!("\"name\": \"{name}\"")).unwrap();
+        assert!(position("x") < position("y"));
+        assert!(position("length()") < position("scale()"));
+    }
+
+    #[test]
+    fn test_summarize_keeps_the_first_paragraph() {
+        let documentation =
+            "\nCreates a person.\nThe name is trimmed.\n\nMore details.\n#### Example\n";
+
+        assert_eq!(
+            summarize(documentation),
+            "Creates a person.\nThe name is trimmed.\n"
+        );
+        assert_eq!(
+            summarize("*Since 1.2*\n\nLoads it.\n```rust\nload();\n```\n"),
+            "*Since 1.2*\n\nLoads it.\n"
+        );
+        assert_eq!(summarize("# Examples\nText\n"), "");
+    }
+
+    #[test]
+    fn test_summarize_keeps_the_reference_definitions() {
+        let documentation = "Parses it, see [the spec][spec] and[^note].\n\nMore text.\n\n\
+            [spec]: https://example.com/spec\n[^note]: Only UTF-8\n    is supported.\n";
+
+        assert_eq!(
+            summarize(documentation),
+            "Parses it, see [the spec][spec] and[^note].\n\n\
+            [spec]: https://example.com/spec\n[^note]: Only UTF-8\n    is supported.\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_only_gathers_the_reference_definitions() {
+        let cli_mock = create_mock_cli(None, None, false, true, OutputFormat::Markdown);
+        let raw_rust_code = String::from(
+            r#"
+            /// Parses it, see [the spec][spec].
+            /// [spec]: https://example.com/spec
+            pub struct Parser {}
+            "#,
+        );
+
+        let output = Processing::new(cli_mock).start(&raw_rust_code);
+
+        let markdown = output.get(&OutputFormat::Markdown).unwrap();
+        assert!(markdown
+            .contains("Parses it, see [the spec][spec].\n\n[spec]: https://example.com/spec\n"));
+    }
+
+    #[test]
+    fn test_sort_methods_keeps_fields_in_source_order() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        cli_mock.sort_methods = SortOrder::Alphabetical;
+        let raw_rust_code = String::from(
+            r#"
+            pub struct Header {
+                pub magic: u32,
+                pub length: u16,
+            }
+            impl Header {
+                pub fn validate(&self) {}
+                pub fn Checksum(&self) {}
+                pub fn decode(bytes: &[u8]) {}
+            }
+            "#,
+        );
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        let markdown = output.get(&OutputFormat::Markdown).unwrap();
+        let headings: Vec<&str> = markdown
+            .lines()
+            .filter(|line| line.starts_with("### "))
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                "### magic",
+                "### length",
+                "### Checksum()",
+                "### decode(bytes: &[u8])",
+                "### validate()"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_since_markers_are_added_to_documented_versions() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        cli_mock.since = Some(String::from(r"(?m)(?:^#+ Since\s+|@since\s+)(\S+)"));
+        let raw_rust_code = String::from(
+            r#"
+            /// A worker. @since 1.0.0
+            struct Worker {}
+            impl Worker {
+                /// Does the thing.
+                ///
+                /// # Since
+                /// 1.2.0
+                pub fn do_thing(&self) {}
+                /// Stops.
+                pub fn stop(&self) {}
+            }
+            "#,
+        );
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        let markdown = output.get(&OutputFormat::Markdown).unwrap();
+        assert!(markdown.contains("\n*Since 1.0.0*\n\nA worker."));
+        assert!(markdown.contains("### do_thing()\n*Since 1.2.0*\n\nDoes the thing."));
+        assert!(markdown.contains("### stop()\nStops."));
+    }
+
+    #[test]
+    fn test_missing_doc_text_fills_undocumented_fields() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        cli_mock.missing_doc_text = Some(String::from("_No documentation available._"));
+        let raw_rust_code = String::from(
+            r#"
+            /// A person.
+            struct Person {
+                /// The name.
+                name: String,
+                age: u32,
+            }
+            "#,
+        );
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        let markdown = output.get(&OutputFormat::Markdown).unwrap();
+        assert!(markdown.contains("### name\nThe name.\n"));
+        assert!(markdown.contains("### age\n_No documentation available._\n"));
+        assert!(!markdown.contains("A person.\n_No documentation"));
+    }
+
+    #[test]
+    fn test_strip_private_doc_keeps_private_members_without_documentation() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        cli_mock.document_private_items = true;
+        cli_mock.strip_private_doc = true;
+        let raw_rust_code = String::from(
+            r#"
+            /// A person.
+            struct Person {
+                /// The public name.
+                pub name: String,
+                /// The internal cache key.
+                key: u64,
+            }
+            "#,
+        );
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        let markdown = output.get(&OutputFormat::Markdown).unwrap();
+        assert!(markdown.contains("### name\nThe public name.\n"));
+        assert!(markdown.contains("### key\n"));
+        assert!(!markdown.contains("internal cache key"));
+    }
+
+    #[test]
+    fn test_split_methods_renders_page_per_method_and_index() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        let raw_rust_code = String::from(
+            r#"
+            struct Person {}
+            impl Person {
+                /// Greets the other person.
+                pub fn greet(&self, other: &Person) {}
+                /// Says goodbye.
+                pub fn leave(&self) {}
+            }
+            "#,
+        );
+
+        let processing = Processing::new(cli_mock);
+        let pages = processing.split_methods(&raw_rust_code);
+
+        assert_eq!(
+            pages,
+            vec![
+                (
+                    String::from("Person_greet.md"),
+                    String::from("## Person::greet(other: &Person)\n\nGreets the other person.\n")
+                ),
+                (
+                    String::from("Person_leave.md"),
+                    String::from("## Person::leave()\n\nSays goodbye.\n")
+                ),
+                (
+                    String::from("Person_methods.md"),
+                    String::from(
+                        "## Person Methods\n\n- [greet(other: &Person)](Person_greet.md)\n- [leave()](Person_leave.md)\n"
+                    )
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_methods_links_relative_to_the_index() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        cli_mock.relative_links = true;
+        cli_mock.file_name_prefix = Some(String::from("api_"));
+        let raw_rust_code = String::from(
+            r#"
+            struct Person {}
+            impl Person {
+                /// Says goodbye.
+                pub fn leave(&self) {}
+            }
+            "#,
+        );
+
+        let pages = Processing::new(cli_mock).split_methods(&raw_rust_code);
+
+        assert_eq!(
+            pages.last().unwrap(),
+            &(
+                String::from("Person_methods.md"),
+                String::from("## Person Methods\n\n- [leave()](api_Person_leave.md)\n")
+            )
+        );
+    }
+
+    #[test]
+    fn test_use_xrefs() {
+        let asciidoc = "== Person Methods\n\n* link:Person_leave.adoc[leave()]\n\
+            * link:https://example.com[Example]\n";
+
+        assert_eq!(
+            use_xrefs(asciidoc, &[String::from("Person_leave.adoc")]),
+            "== Person Methods\n\n* xref:Person_leave.adoc[leave()]\n\
+            * link:https://example.com[Example]\n"
+        );
+    }
+
+    #[test]
+    fn test_item_macro_is_skipped_with_warning() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        let raw_rust_code = String::from(
+            r#"
+            struct Page { title: String }
+            html! { <div>{ "generated" }</div> }
+            "#,
+        );
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        assert!(output
+            .get(&OutputFormat::Markdown)
+            .unwrap()
+            .contains("## Page"));
+        assert_eq!(processing.diagnostics.warnings().len(), 1);
+        assert!(processing.diagnostics.warnings()[0].contains("'html!'"));
+    }
+
+    #[test]
+    fn test_markdown_skips_test_modules_unless_included() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        let raw_rust_code = String::from(
+            r#"
+            mod model {
+                /// An order.
+                pub struct Order { pub id: u32 }
+            }
+            #[cfg(test)]
+            mod tests {
+                impl super::model::Order {
+                    /// Creates an order for the tests.
+                    pub fn fixture() -> Self { Self { id: 1 } }
+                }
+            }
+            "#,
+        );
+
+        let output = Processing::new(cli_mock.clone()).start(&raw_rust_code);
+        cli_mock.include_tests = true;
+        let output_with_tests = Processing::new(cli_mock).start(&raw_rust_code);
+
+        let markdown = output.get(&OutputFormat::Markdown).unwrap();
+        let markdown_with_tests = output_with_tests.get(&OutputFormat::Markdown).unwrap();
+        assert!(markdown.contains("## Order"));
+        assert!(!markdown.contains("fixture()"));
+        assert!(markdown_with_tests.contains("### fixture()\nCreates an order for the tests.\n"));
+    }
+
+    #[test]
+    fn test_additional_outputs_do_not_repeat_the_warnings() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        let raw_rust_code = String::from(
+            r#"
+            struct Page { title: String }
+            impl Page { pub fn show(&self) {} }
+            html! { <div>{ "generated" }</div> }
+            "#,
+        );
+
+        let mut processing = Processing::new(cli_mock);
+        processing.start(&raw_rust_code);
+        processing.split_methods(&raw_rust_code);
+        processing.extract_examples(&raw_rust_code);
+        processing.glossary(&raw_rust_code);
+        processing.verify(&raw_rust_code);
+
+        assert_eq!(processing.diagnostics.warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_check_diagram_methods_reports_disagreement() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Person"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: vec![Method {
+                name: String::from("greet(other: &Person)"),
+                ..Default::default()
+            }],
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let plantuml =
+            "@startuml\n\nclass \"Person\" {\n    + leave()\n    - forget()\n}\n\n@enduml";
+        let mut diagnostics = Diagnostics::default();
+
+        check_diagram_methods(&class, plantuml, &mut diagnostics);
+
+        assert_eq!(
+            diagnostics.warnings(),
+            [
+                "Method 'greet' of 'Person' is documented, but missing in the diagram",
+                "Method 'leave' of 'Person' is in the diagram, but missing in the documentation",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_diagram_methods_ignores_fields_with_parentheses() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Window"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: vec![Method {
+                name: String::from("area()"),
+                ..Default::default()
+            }],
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let plantuml = "@startuml\n\nclass \"Window\" {\n    + size: (u32, u32)\n    \
+            + callback: fn(u8)\n    + area(): u32\n}\n\n@enduml";
+        let mut diagnostics = Diagnostics::default();
+
+        check_diagram_methods(&class, plantuml, &mut diagnostics);
+
+        assert!(diagnostics.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_per_method_diagrams() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Asciidoc);
+        let raw_rust_code = String::from(
+            r#"
+            struct Person { name: String }
+            impl Person {
+                pub fn new(name: String) -> Self { Person { name } }
+                fn introduce(&self) {}
+            }
+            "#,
+        );
+        let processing = Processing::new(cli_mock);
+        let diagrams = processing.per_method_diagrams(&raw_rust_code);
+
+        assert_eq!(diagrams.len(), 2);
+        assert!(diagrams[0].contains("    + <b>new(name: String)"));
+        assert!(!diagrams[0].contains("introduce"));
+        assert!(diagrams[1].starts_with("@startuml\n\nclass \"Person\" {\n    - <b>introduce("));
+        assert!(diagrams[1].ends_with("</b>\n}\n\n@enduml\n"));
+        assert!(!diagrams[1].contains("new("));
+    }
+
+    #[test]
+    fn test_per_method_diagrams_keep_the_header_of_the_class_diagram() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Asciidoc);
+        cli_mock.plantuml_struct_keyword = StructKeyword::Struct;
+        let raw_rust_code = String::from(
+            r#"
+            struct Stack<T> { items: Vec<T> }
+            impl<T> Stack<T> {
+                pub fn push(&mut self, item: T) {}
+            }
+            "#,
+        );
+
+        let processing = Processing::new(cli_mock);
+        let diagrams = processing.per_method_diagrams(&raw_rust_code);
+
+        assert_eq!(diagrams.len(), 1);
+        assert!(diagrams[0].starts_with("@startuml\n\nstruct \"Stack\" <T> {\n    + <b>push("));
+        assert!(!diagrams[0].contains("items"));
+    }
+
+    #[test]
+    fn test_extract_examples() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        let raw_rust_code = String::from(
+            r#"
+            /// A person.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// # use people::Person;
+            /// let person = Person::new();
+            /// ```
+            struct Person {}
+            impl Person {
+                /// Saves the person.
+                ///
+                /// ```text
+                /// not an example section
+                /// ```
+                ///
+                /// #### Example
+                /// ```rust,no_run
+                /// person.save();
+                /// ```
+                /// ```text
+                /// not rust
+                /// ```
+                pub fn save(&self) {}
+            }
+            "#,
+        );
+
+        let processing = Processing::new(cli_mock);
+        let examples = processing.extract_examples(&raw_rust_code);
+
+        assert_eq!(
+            examples,
+            vec![
+                (
+                    String::from("person_1.rs"),
+                    String::from(
+                        "#[test]\nfn person_1() {\n    use people::Person;\n    let person = Person::new();\n}\n"
+                    )
+                ),
+                (
+                    String::from("person_save_1.rs"),
+                    String::from(
+                        "// no_run\n#[test]\n#[ignore]\nfn person_save_1() {\n    person.save();\n}\n"
+                    )
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_round_trip_has_no_differences() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        let raw_rust_code = String::from(
+            r#"
+            /// A person.
+            struct Person {
+                /// The name.
+                name: String,
+            }
+            impl Person {
+                /// Says hello.
+                ///
+                /// #### Example
+                fn greet(&self, other: &Person) {}
+            }
+            "#,
+        );
+
+        let processing = Processing::new(cli_mock);
+        let differences = processing.verify(&raw_rust_code);
+
+        assert!(differences.is_empty());
+    }
+
+    #[test]
+    fn test_verify_reports_differences() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        let raw_rust_code = String::from(
+            r#"
+            struct Person {
+                name: String,
+            }
+            impl Person {
+                /// ```text
+                fn greet(&self) {}
+                fn leave(&self) {}
+            }
+            "#,
+        );
+        let expected_differences = vec![
+            "~ methods: expected 2, found 1",
+            "- missing method 'leave()'",
+        ];
+
+        let processing = Processing::new(cli_mock);
+        let differences = processing.verify(&raw_rust_code);
+
+        assert_eq!(differences, expected_differences);
+    }
+
+    #[test]
+    fn test_process_input_format_asciidoc() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Asciidoc);
+        let raw_rust_code = String::from("struct Person { name: String }");
+        let expected_headline = "== Person";
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        let expected_output_format = &OutputFormat::Asciidoc;
+        assert!(output.contains_key(expected_output_format));
+        assert!(output.get(expected_output_format).is_some());
         assert!(output
             .get(expected_output_format)
             .unwrap()
             .contains(expected_headline));
     }
 
+    #[test]
+    fn test_process_input_format_asciidoc_with_toc() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Asciidoc);
+        cli_mock.toc = true;
+        let raw_rust_code = String::from("struct Person { name: String }");
+        let expected_header = "= Person\n:toc:\n:toclevels: 3\n\n";
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        assert!(output
+            .get(&OutputFormat::Asciidoc)
+            .unwrap()
+            .starts_with(expected_header));
+    }
+
+    #[test]
+    fn test_process_input_format_asciidoc_with_author_and_revdate() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Asciidoc);
+        cli_mock.title = Some(String::from("Building Block View"));
+        cli_mock.author = Some(String::from("Jane Doe"));
+        cli_mock.revdate = Some(String::from("2024-01-31"));
+        let raw_rust_code = String::from("struct Person { name: String }");
+        let expected_header = "= Building Block View\nJane Doe\n:revdate: 2024-01-31\n\n";
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        assert!(output
+            .get(&OutputFormat::Asciidoc)
+            .unwrap()
+            .starts_with(expected_header));
+    }
+
+    #[test]
+    fn test_process_input_format_markdown_has_no_header() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        cli_mock.author = Some(String::from("Jane Doe"));
+        let raw_rust_code = String::from("struct Person { name: String }");
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        assert!(output
+            .get(&OutputFormat::Markdown)
+            .unwrap()
+            .starts_with("## Person"));
+    }
+
+    #[test]
+    fn test_normalize_whitespace_strips_trailing_spaces_and_newlines() {
+        let text = "== Title  \r\n\nSome text\t\n\n\n";
+
+        assert_eq!(normalize_whitespace(text), "== Title\n\nSome text\n");
+        assert_eq!(normalize_whitespace("No newline"), "No newline\n");
+    }
+
+    #[test]
+    fn test_include_plantuml_replaces_embedded_diagram() {
+        let asciidoc = "== Person\n\n[plantuml]\n----\n@startuml\nclass \"Person\"\n@enduml\n----\n\nA person.\n";
+
+        let included = include_plantuml(asciidoc, "docs/person.puml");
+
+        assert_eq!(
+            included,
+            "== Person\n\nplantuml::docs/person.puml[]\n\nA person.\n"
+        );
+    }
+
+    #[test]
+    fn test_process_input_output_formats_are_ordered() {
+        let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::AsciidocPlantuml);
+        let raw_rust_code = String::from("struct Person { name: String }");
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        let output_formats: Vec<&OutputFormat> = output.keys().collect();
+        assert_eq!(
+            output_formats,
+            vec![&OutputFormat::Asciidoc, &OutputFormat::AsciidocPlantuml]
+        );
+    }
+
+    #[test]
+    fn test_process_input_format_asciidoc_with_diagram_fence() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Asciidoc);
+        cli_mock.diagram_fence = String::from("puml");
+        let raw_rust_code = String::from("struct Person { name: String }");
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        let asciidoc = output.get(&OutputFormat::Asciidoc).unwrap();
+        assert!(asciidoc.contains("[plantuml]"));
+        assert!(!asciidoc.contains("[source,puml]"));
+    }
+
     #[test]
     fn test_process_input_format_asciidoc_plantuml() {
         let cli_mock = create_mock_cli(None, None, false, false, OutputFormat::AsciidocPlantuml);
@@ -338,7 +2095,7 @@ mod tests {
         let expected_headline = "== Person";
         let expected_class_definition = "class \"Person\" {";
 
-        let processing = Processing { args: cli_mock };
+        let mut processing = Processing::new(cli_mock);
         let output = processing.start(&raw_rust_code);
 
         let expected_output_format1 = &OutputFormat::Asciidoc;
@@ -360,4 +2117,72 @@ mod tests {
             .unwrap()
             .contains("@enduml"));
     }
+
+    #[test]
+    fn test_process_input_with_no_plantuml_has_no_diagram() {
+        let mut cli_mock =
+            create_mock_cli(None, None, false, false, OutputFormat::AsciidocPlantuml);
+        cli_mock.no_plantuml = true;
+        let raw_rust_code = String::from("struct Person { name: String }");
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&raw_rust_code);
+
+        assert!(!output.contains_key(&OutputFormat::AsciidocPlantuml));
+        let asciidoc = output.get(&OutputFormat::Asciidoc).unwrap();
+        assert!(asciidoc.contains("== Person"));
+        assert!(!asciidoc.contains("[plantuml]"));
+        assert!(!asciidoc.contains("@startuml"));
+    }
+
+    #[test]
+    fn test_process_input_keeps_small_diagram_inline() {
+        let raw_rust_code = String::from("struct Person { name: String }");
+        let mut inline_cli =
+            create_mock_cli(None, None, false, false, OutputFormat::AsciidocPlantuml);
+        inline_cli.inline_diagrams = Some(20);
+        let mut extracting_cli = inline_cli.clone();
+        extracting_cli.inline_diagrams = Some(2);
+
+        let inline_output = Processing::new(inline_cli).start(&raw_rust_code);
+        let extracting_output = Processing::new(extracting_cli).start(&raw_rust_code);
+
+        assert!(!inline_output.contains_key(&OutputFormat::AsciidocPlantuml));
+        let asciidoc = inline_output.get(&OutputFormat::Asciidoc).unwrap();
+        assert!(asciidoc.contains("[plantuml]"));
+        assert!(asciidoc.contains("@startuml"));
+        assert!(extracting_output.contains_key(&OutputFormat::AsciidocPlantuml));
+    }
+
+    #[test]
+    fn test_process_input_without_types_has_no_empty_diagram() {
+        let raw_rust_code = String::from("use std::fmt;\n\nconst LIMIT: u32 = 10;");
+        let mut markdown_cli = create_mock_cli(None, None, false, false, OutputFormat::Markdown);
+        markdown_cli.emit_plantuml_only_when_nonempty = true;
+        let mut plantuml_cli = markdown_cli.clone();
+        plantuml_cli.format = OutputFormat::Plantuml;
+
+        let markdown_output = Processing::new(markdown_cli).start(&raw_rust_code);
+        let plantuml_output = Processing::new(plantuml_cli).start(&raw_rust_code);
+
+        let markdown = markdown_output.get(&OutputFormat::Markdown).unwrap();
+        assert!(!markdown.contains("```plantuml"));
+        assert!(plantuml_output.is_empty());
+    }
+
+    #[test]
+    fn test_markdown_input_is_converted_without_parsing_rust() {
+        let mut cli_mock = create_mock_cli(None, None, false, false, OutputFormat::Asciidoc);
+        cli_mock.input_kind = InputKind::Markdown;
+        let markdown = String::from("# Title\n\n## Subtitle\n\nSome text");
+
+        let mut processing = Processing::new(cli_mock);
+        let output = processing.start(&markdown);
+
+        let asciidoc = output.get(&OutputFormat::Asciidoc).unwrap();
+        assert_eq!(
+            asciidoc.replace("\r\n", "\n"),
+            "== Title\n\n=== Subtitle\n\nSome text\n"
+        );
+    }
 }
@@ -0,0 +1,16 @@
+use crate::model::class_object::Class;
+
+pub mod asciidoc_renderer;
+pub mod json_renderer;
+pub mod markdown_renderer;
+pub mod plantuml_renderer;
+pub mod toml_renderer;
+
+/// Renders the parsed classes into the content of one output format.
+///
+/// Every output format has its own implementation, so a new format only
+/// requires a new renderer and its registration in the processing.
+pub trait Renderer {
+    /// Renders the given classes into a single string.
+    fn render(&self, classes: &[Class]) -> String;
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use crate::model::class_object::Class;
+use crate::parser::asciidoc_parser::AsciidocParser;
+use crate::renderer::markdown_renderer::MarkdownRenderer;
+use crate::renderer::Renderer;
+
+/// Renders classes to AsciiDoc by converting their Markdown rendering with pandoc.
+///
+/// If any of the header fields is set, a document header is put in front of the
+/// output, which turns the generated fragment into a standalone document.
+#[derive(Default)]
+pub struct AsciidocRenderer {
+    /// Title of the document header. Defaults to the name of the first class.
+    pub(crate) title: Option<String>,
+    /// Author line of the document header.
+    pub(crate) author: Option<String>,
+    /// Revision date of the document header.
+    pub(crate) revdate: Option<String>,
+    /// Whether the document header enables a table of contents.
+    pub(crate) toc: bool,
+    /// The renderer of the Markdown, which is converted to AsciiDoc.
+    pub(crate) markdown_renderer: MarkdownRenderer,
+    /// The time pandoc may take for the conversion, 30 seconds if not set.
+    pub(crate) pandoc_timeout: Option<Duration>,
+    /// Whether a conversion which timed out is retried once.
+    pub(crate) pandoc_retry: bool,
+    /// Whether the Rust blocks are passed through verbatim instead of by pandoc.
+    pub(crate) verbatim_examples: bool,
+    /// Whether the lines of the assertions in the Rust blocks are highlighted.
+    pub(crate) highlight_asserts: bool,
+}
+
+impl Renderer for AsciidocRenderer {
+    fn render(&self, classes: &[Class]) -> String {
+        let markdown_output = self.markdown_renderer.render(classes);
+        let mut ascii_doc_parser = AsciidocParser::new(None)
+            .with_diagram_fence(&self.markdown_renderer.diagram_fence)
+            .with_retry(self.pandoc_retry)
+            .with_verbatim_examples(self.verbatim_examples)
+            .with_highlight_asserts(self.highlight_asserts);
+        if let Some(timeout) = self.pandoc_timeout {
+            ascii_doc_parser = ascii_doc_parser.with_timeout(timeout);
+        }
+        let asciidoc_output = match ascii_doc_parser.parse_from_markdown(&markdown_output) {
+            Ok(asciidoc_string) => asciidoc_string,
+            Err(e) => {
+                panic!("Failed to parse markdown to asciidoc: '{}'", e);
+            }
+        };
+
+        if self.has_header() {
+            let default_title = classes.first().map(|class| class.name.as_str());
+            let title = self.title.as_deref().or(default_title).unwrap_or_default();
+            self.build_header(title) + &asciidoc_output
+        } else {
+            asciidoc_output
+        }
+    }
+}
+
+impl AsciidocRenderer {
+    /// Returns true if any of the header fields requires a document header.
+    fn has_header(&self) -> bool {
+        self.toc || self.title.is_some() || self.author.is_some() || self.revdate.is_some()
+    }
+
+    /// Builds the document header with the given title, the optional author line,
+    /// revision date and table of contents.
+    fn build_header(&self, title: &str) -> String {
+        let mut header = format!("= {title}\n");
+        if let Some(author) = &self.author {
+            header.push_str(&format!("{author}\n"));
+        }
+        if let Some(revdate) = &self.revdate {
+            header.push_str(&format!(":revdate: {revdate}\n"));
+        }
+        if self.toc {
+            header.push_str(":toc:\n:toclevels: 3\n");
+        }
+        header.push('\n');
+        header
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_header_with_all_fields() {
+        let renderer = AsciidocRenderer {
+            title: None,
+            author: Some(String::from("Jane Doe")),
+            revdate: Some(String::from("2024-01-31")),
+            toc: true,
+            ..Default::default()
+        };
+        let expected_header = "= Person\nJane Doe\n:revdate: 2024-01-31\n:toc:\n:toclevels: 3\n\n";
+
+        assert!(renderer.has_header());
+        assert_eq!(renderer.build_header("Person"), expected_header);
+    }
+}
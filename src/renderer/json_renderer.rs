@@ -0,0 +1,21 @@
+use crate::model::class_object::Class;
+use crate::renderer::Renderer;
+
+/// Renders classes to pretty printed JSON as described by the JSON Schema of the model.
+///
+/// A single class is rendered as JSON object, multiple classes as JSON array.
+/// The keys keep the order of the model and the members their order in the class,
+/// the source order unless sorted with '--sort-fields' and '--sort-methods'. So
+/// the output of unchanged code is identical and committed JSON diffs cleanly.
+#[derive(Default)]
+pub struct JsonRenderer {}
+
+impl Renderer for JsonRenderer {
+    fn render(&self, classes: &[Class]) -> String {
+        let json = match classes {
+            [class] => serde_json::to_string_pretty(class),
+            _ => serde_json::to_string_pretty(classes),
+        };
+        json.expect("Failed to serialize to JSON")
+    }
+}
@@ -0,0 +1,1157 @@
+use serde::Deserialize;
+
+use crate::model::class_object::{Class, Method};
+use crate::renderer::Renderer;
+
+/// Renders classes to Markdown, which is also the base for the AsciiDoc output.
+///
+/// Each class is rendered as a level two heading followed by its PlantUML
+/// diagram and documentation. Each field and method is rendered as a level
+/// three heading with its documentation.
+pub struct MarkdownRenderer {
+    /// The language tag of the fenced PlantUML block, e.g. `plantuml`, `puml` or `uml`.
+    pub(crate) diagram_fence: String,
+    /// Whether a table of contents linking to the sections is put in front.
+    pub(crate) toc: bool,
+    /// Whether the method headings get anchors, which are linked from a list of
+    /// the methods below the documentation of the class. The anchors use the
+    /// pandoc heading attribute syntax, so they are meant for the AsciiDoc output.
+    pub(crate) method_anchors: bool,
+    /// The texts and levels of the generated headings.
+    pub(crate) headings: HeadingConfig,
+    /// Whether the methods are grouped into the public API and the internal ones.
+    pub(crate) group_by_visibility: bool,
+    /// Whether the builder methods get a subsection of their own.
+    pub(crate) group_builders: bool,
+    /// Whether the fields and methods are rendered as one list in source order.
+    pub(crate) unified_members: bool,
+    /// Whether the fields and methods are rendered as entries of a definition list
+    /// instead of headings. The lists use the pandoc syntax, so they are meant for
+    /// the AsciiDoc output.
+    pub(crate) definition_lists: bool,
+}
+
+impl Default for MarkdownRenderer {
+    fn default() -> Self {
+        MarkdownRenderer {
+            diagram_fence: String::from("plantuml"),
+            toc: false,
+            method_anchors: false,
+            headings: HeadingConfig::default(),
+            group_by_visibility: false,
+            group_builders: false,
+            unified_members: false,
+            definition_lists: false,
+        }
+    }
+}
+
+/// The texts and the level of the headings generated for the sections of the
+/// model, read from a TOML file like
+///
+/// ```toml
+/// level = 2
+/// constants = "Constants"
+/// reexports = "Re-exports"
+/// traits = "Traits"
+/// trait_implementations = "Trait Implementations"
+/// foreign = "Foreign"
+/// methods = "Methods"
+/// public_api = "Public API"
+/// internal = "Internal"
+/// builder_methods = "Builder Methods"
+/// members = "Members"
+/// ```
+///
+/// The class and the sections share the configured level, their members are one
+/// level below and the members of traits and trait implementations two levels
+/// below. The levels are those of the Markdown, pandoc converts level two to an
+/// AsciiDoc level one section.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeadingConfig {
+    /// The level of the class heading and the section headings.
+    pub level: usize,
+    /// The heading of the section of the module level constants.
+    pub constants: String,
+    /// The heading of the section of the `pub use` re-exports.
+    pub reexports: String,
+    /// The heading of the section of the traits defined in the module.
+    pub traits: String,
+    /// The heading of the section of the trait implementations.
+    pub trait_implementations: String,
+    /// The heading of the sections of the `extern` blocks, followed by the ABI.
+    pub foreign: String,
+    /// The label of the list linking to the methods, see '--method-anchors'.
+    pub methods: String,
+    /// The label of the public methods, see '--group-by-visibility'.
+    pub public_api: String,
+    /// The label of the crate visible and private methods, see '--group-by-visibility'.
+    pub internal: String,
+    /// The heading of the builder methods, see '--group-builders'.
+    pub builder_methods: String,
+    /// The heading of the fields and methods, see '--unified-members'.
+    pub members: String,
+}
+
+impl Default for HeadingConfig {
+    fn default() -> Self {
+        HeadingConfig {
+            level: 2,
+            constants: String::from("Constants"),
+            reexports: String::from("Re-exports"),
+            traits: String::from("Traits"),
+            trait_implementations: String::from("Trait Implementations"),
+            foreign: String::from("Foreign"),
+            methods: String::from("Methods"),
+            public_api: String::from("Public API"),
+            internal: String::from("Internal"),
+            builder_methods: String::from("Builder Methods"),
+            members: String::from("Members"),
+        }
+    }
+}
+
+impl HeadingConfig {
+    /// Reads the heading configuration from a TOML file. Missing entries keep
+    /// their default.
+    pub fn from_toml_file(path: &str) -> Result<Self, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read '{path}': {e}"))?;
+        let config: HeadingConfig =
+            toml::from_str(&content).map_err(|e| format!("Failed to parse '{path}': {e}"))?;
+        if !(1..=4).contains(&config.level) {
+            return Err(format!(
+                "The heading level in '{path}' has to be between 1 and 4, as the members are nested two levels below"
+            ));
+        }
+        Ok(config)
+    }
+}
+
+impl Renderer for MarkdownRenderer {
+    fn render(&self, classes: &[Class]) -> String {
+        let markdown = classes
+            .iter()
+            .map(|class| self.render_class(class))
+            .collect::<Vec<String>>()
+            .join("\n");
+        if self.toc {
+            render_toc(&markdown) + &markdown
+        } else {
+            markdown
+        }
+    }
+}
+
+/// Renders a table of contents as a nested list of links to the level two and
+/// three headings of the Markdown.
+///
+/// The anchors follow the GitHub convention: lowercase, punctuation removed,
+/// spaces replaced by dashes and a counter appended to repeated anchors.
+fn render_toc(markdown: &str) -> String {
+    let mut toc = String::from("**Contents**\n\n");
+    let mut anchors: Vec<String> = Vec::new();
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        if in_code_block {
+            continue;
+        }
+        let level = line
+            .chars()
+            .take_while(|character| *character == '#')
+            .count();
+        let Some(heading) = line[level..].strip_prefix(' ') else {
+            continue;
+        };
+        if level == 0 {
+            continue;
+        }
+        let slug = heading_anchor(heading);
+        let occurrences = anchors.iter().filter(|anchor| **anchor == slug).count();
+        anchors.push(slug.clone());
+        let anchor = match occurrences {
+            0 => slug,
+            count => format!("{slug}-{count}"),
+        };
+        if level == 2 || level == 3 {
+            let indentation = "  ".repeat(level - 2);
+            toc.push_str(&format!("{indentation}- [{}](#{anchor})\n", heading.trim()));
+        }
+    }
+    toc.push('\n');
+    toc
+}
+
+/// Creates the anchor GitHub generates for a heading.
+fn heading_anchor(heading: &str) -> String {
+    heading
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|character| match character {
+            ' ' => Some('-'),
+            '-' | '_' => Some(character),
+            character if character.is_alphanumeric() => Some(character),
+            _ => None,
+        })
+        .collect()
+}
+
+impl MarkdownRenderer {
+    /// Renders a single class to Markdown.
+    ///
+    /// The levels of the headings are computed from the nesting of the model, see
+    /// [HeadingConfig], and the headings within the documentation are nested below
+    /// the heading of the documented item.
+    fn render_class(&self, documentation: &Class) -> String {
+        let headings = &self.headings;
+        let section_level = headings.level;
+        let member_level = section_level + 1;
+        let mut output_buffer = String::new();
+
+        output_buffer.push_str(&format!(
+            "{} {}\n",
+            "#".repeat(section_level),
+            documentation.name
+        ));
+        // without a diagram, e.g. with '--no-plantuml', the block is left out
+        if !documentation.plantuml.is_empty() {
+            output_buffer.push_str(
+                format!(
+                    "```{}\n{}\n```\n",
+                    self.diagram_fence, documentation.plantuml
+                )
+                .as_str(),
+            );
+        }
+        let class_documentation = nest_headings(&documentation.documentation, member_level + 1);
+        output_buffer.push_str(format!("\n{class_documentation}\n").as_str());
+
+        //output the overview of the methods linking to their sections
+        if self.has_method_anchors() && !documentation.methods.is_empty() {
+            output_buffer.push_str(format!("\n**{}**\n\n", headings.methods).as_str());
+            for method in &documentation.methods {
+                let anchor = method_anchor(&documentation.name, &method.name);
+                output_buffer.push_str(format!("- [{}](#{anchor})\n", method.name).as_str());
+            }
+        }
+
+        if self.unified_members {
+            output_buffer.push_str(&self.render_unified_members(documentation, member_level));
+        } else {
+            output_buffer.push_str(&self.render_fields_and_methods(documentation, member_level));
+        }
+
+        //output the module level constants in an own section
+        if !documentation.constants.is_empty() {
+            output_buffer.push_str(&render_heading(section_level, &headings.constants));
+            for constant in &documentation.constants {
+                output_buffer.push_str(&render_member(
+                    member_level,
+                    &constant.name,
+                    &constant.documentation,
+                ));
+            }
+        }
+
+        //output the re-exports, the facade of the module, in an own section
+        if !documentation.reexports.is_empty() {
+            output_buffer.push_str(&render_heading(section_level, &headings.reexports));
+            for reexport in &documentation.reexports {
+                output_buffer.push_str(&render_member(
+                    member_level,
+                    &format!(
+                        "{} use {}",
+                        reexport.visibility.as_deref().unwrap_or("pub"),
+                        reexport.name
+                    ),
+                    &reexport.documentation,
+                ));
+            }
+        }
+
+        //output the traits defined in the module with their methods in an own section
+        if !documentation.traits.is_empty() {
+            output_buffer.push_str(&render_heading(section_level, &headings.traits));
+            for defined_trait in &documentation.traits {
+                output_buffer.push_str(&render_member(
+                    member_level,
+                    &format!("trait {}", defined_trait.name),
+                    &defined_trait.documentation,
+                ));
+                let object_safe = if defined_trait.object_safe {
+                    "yes"
+                } else {
+                    "no"
+                };
+                output_buffer.push_str(format!("_Object-safe: {object_safe}_\n").as_str());
+                for method in &defined_trait.methods {
+                    let kind = if method.provided {
+                        "default"
+                    } else {
+                        "required"
+                    };
+                    output_buffer.push_str(&render_member(
+                        member_level + 1,
+                        &format!("{} ({kind})", method.name),
+                        &method.documentation,
+                    ));
+                }
+            }
+        }
+
+        //output the trait implementations with their constants and methods in an own section
+        if !documentation.trait_impls.is_empty() {
+            output_buffer.push_str(&render_heading(
+                section_level,
+                &headings.trait_implementations,
+            ));
+            for trait_impl in &documentation.trait_impls {
+                output_buffer.push_str(&render_member(
+                    member_level,
+                    &format!("impl {}", link_trait(&trait_impl.name)),
+                    &trait_impl.documentation,
+                ));
+                for member in trait_impl.constants.iter().chain(&trait_impl.methods) {
+                    output_buffer.push_str(&render_member(
+                        member_level + 1,
+                        &member.name,
+                        &member.documentation,
+                    ));
+                }
+            }
+        }
+
+        //output the foreign functions and statics in an own section per extern block
+        for foreign_mod in &documentation.foreign_mods {
+            let heading = format!("{} (extern \"{}\")", headings.foreign, foreign_mod.abi);
+            output_buffer.push_str(&render_heading(section_level, &heading));
+            for item in &foreign_mod.items {
+                output_buffer.push_str(&render_member(
+                    member_level,
+                    &item.name,
+                    &item.documentation,
+                ));
+            }
+        }
+
+        output_buffer
+    }
+
+    /// Renders the fields and then the methods of the class, optionally grouped
+    /// by their visibility and with the builder methods in a subsection.
+    fn render_fields_and_methods(&self, class: &Class, member_level: usize) -> String {
+        let headings = &self.headings;
+        let mut output = String::new();
+
+        //output all fields with its documentation in an markdown list
+        for field in &class.fields {
+            output.push_str(&self.render_member(
+                member_level,
+                &self.field_name(field),
+                &field.documentation,
+            ));
+        }
+
+        let (builders, methods): (Vec<&Method>, Vec<&Method>) = class
+            .methods
+            .iter()
+            .partition(|method| self.group_builders && method.builder);
+
+        //output each method with its documentation in an markdown list, optionally
+        //grouped by the visibility
+        let method_groups = if self.group_by_visibility {
+            let (public, internal): (Vec<&Method>, Vec<&Method>) = methods
+                .into_iter()
+                .partition(|method| method.visibility.as_deref() == Some("pub"));
+            vec![
+                (Some(&headings.public_api), public),
+                (Some(&headings.internal), internal),
+            ]
+        } else {
+            vec![(None, methods)]
+        };
+        for (label, methods) in method_groups {
+            if methods.is_empty() {
+                continue;
+            }
+            if let Some(label) = label {
+                output.push_str(format!("\n**{label}**\n").as_str());
+            }
+            for method in methods {
+                output.push_str(&self.render_member(
+                    member_level,
+                    &self.method_name(class, method),
+                    &method.documentation,
+                ));
+            }
+        }
+
+        //output the builder methods in a subsection below the other methods
+        if !builders.is_empty() {
+            output.push_str(&render_heading(member_level, &headings.builder_methods));
+            for method in builders {
+                output.push_str(&self.render_member(
+                    member_level + 1,
+                    &self.method_name(class, method),
+                    &method.documentation,
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Renders the fields and methods of the class interleaved in source order in
+    /// a single `Members` subsection, each tagged as field or method.
+    fn render_unified_members(&self, class: &Class, member_level: usize) -> String {
+        let mut members: Vec<(&Method, bool)> = class
+            .fields
+            .iter()
+            .map(|field| (field, true))
+            .chain(class.methods.iter().map(|method| (method, false)))
+            .collect();
+        if members.is_empty() {
+            return String::new();
+        }
+        // members without a line, e.g. read back from Markdown, keep their order
+        members.sort_by_key(|(member, _)| member.line.unwrap_or(usize::MAX));
+
+        let mut output = render_heading(member_level, &self.headings.members);
+        for (member, is_field) in members {
+            let (name, tag) = if is_field {
+                (self.field_name(member), "Field")
+            } else {
+                (self.method_name(class, member), "Method")
+            };
+            output.push_str(&render_heading(member_level + 1, &name));
+            output.push_str(&format!("_{tag}_\n\n"));
+            output.push_str(&nest_headings(&member.documentation, member_level + 2));
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Renders a member as heading with its documentation, or as entry of a
+    /// definition list with the documentation as its definition.
+    fn render_member(&self, level: usize, name: &str, documentation: &str) -> String {
+        if self.definition_lists {
+            render_definition(name, documentation)
+        } else {
+            render_member(level, name, documentation)
+        }
+    }
+
+    /// The heading of a field. A field of type `Option<T>` is marked optional
+    /// with its inner type.
+    fn field_name(&self, field: &Method) -> String {
+        match &field.optional_type {
+            Some(inner_type) => format!("{} (optional): {inner_type}", field.name),
+            None => field.name.clone(),
+        }
+    }
+
+    /// Whether the methods get anchors. The terms of a definition list can't carry
+    /// the heading attribute of an anchor, so there are none with definition lists.
+    fn has_method_anchors(&self) -> bool {
+        self.method_anchors && !self.definition_lists
+    }
+
+    /// The heading of a method, with its anchor if the methods are linked.
+    fn method_name(&self, class: &Class, method: &Method) -> String {
+        if self.has_method_anchors() {
+            let anchor = method_anchor(&class.name, &method.name);
+            format!("{} {{#{anchor}}}", method.name)
+        } else {
+            method.name.clone()
+        }
+    }
+}
+
+/// Renders a heading of the given level, preceded by an empty line.
+fn render_heading(level: usize, text: &str) -> String {
+    format!("\n{} {text}\n", "#".repeat(level))
+}
+
+/// Renders the heading of a member with its documentation, whose headings are
+/// nested below the member heading.
+fn render_member(level: usize, name: &str, documentation: &str) -> String {
+    format!(
+        "{}{}\n",
+        render_heading(level, name),
+        nest_headings(documentation, level + 1)
+    )
+}
+
+/// The definition of an undocumented member in a definition list.
+const UNDOCUMENTED_DEFINITION: &str = "_Undocumented._";
+
+/// Renders a member as entry of a pandoc definition list, with the lines of the
+/// documentation indented to belong to the definition. An undocumented member
+/// gets a placeholder, as a definition can't be empty.
+///
+/// ```markdown
+/// name
+/// :   The name of the person.
+/// ```
+fn render_definition(name: &str, documentation: &str) -> String {
+    let documentation = match documentation.trim_end() {
+        "" => UNDOCUMENTED_DEFINITION,
+        documentation => documentation,
+    };
+    let definition: Vec<String> = documentation
+        .lines()
+        .enumerate()
+        .map(|(index, line)| match (index, line) {
+            (0, line) => line.to_string(),
+            (_, "") => String::new(),
+            (_, line) => format!("    {line}"),
+        })
+        .collect();
+    format!("\n{name}\n:   {}\n", definition.join("\n"))
+}
+
+/// Moves the headings of the documentation down, so the highest one has at least
+/// the given level. A `# Arguments` section of a method documentation thus can't
+/// end up on the level of the methods. Headings in code blocks are left alone.
+fn nest_headings(documentation: &str, min_level: usize) -> String {
+    let heading_level = |line: &str| {
+        let level = line
+            .chars()
+            .take_while(|character| *character == '#')
+            .count();
+        (level > 0 && line[level..].starts_with(' ')).then_some(level)
+    };
+    let mut in_code_block = false;
+    let mut highest_level = None;
+    for line in documentation.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        if let (false, Some(level)) = (in_code_block, heading_level(line)) {
+            highest_level = Some(highest_level.map_or(level, |highest: usize| highest.min(level)));
+        }
+    }
+    let shift = match highest_level {
+        Some(highest) if highest < min_level => min_level - highest,
+        _ => return documentation.to_string(),
+    };
+
+    let mut nested = String::new();
+    in_code_block = false;
+    for line in documentation.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        match heading_level(line) {
+            Some(level) if !in_code_block => {
+                nested.push_str(&"#".repeat((level + shift).min(6)));
+                nested.push_str(&line[level..]);
+            }
+            _ => nested.push_str(line),
+        }
+    }
+    nested
+}
+
+/// Creates the anchor of a method section from the class name without its
+/// generic parameters and the method name without its parameters, e.g.
+/// `Person_introduce`, also for `Person<T>`.
+fn method_anchor(class_name: &str, method_name: &str) -> String {
+    let class_name = class_name.split('<').next().unwrap_or_default().trim();
+    let name = method_name.split('(').next().unwrap_or_default().trim();
+    format!("{class_name}_{name}")
+}
+
+/// The traits of the standard library which are linked to their documentation,
+/// with the module they are defined in.
+const STD_TRAIT_MODULES: &[(&str, &str)] = &[
+    ("AsMut", "convert"),
+    ("AsRef", "convert"),
+    ("Clone", "clone"),
+    ("Copy", "marker"),
+    ("Debug", "fmt"),
+    ("Default", "default"),
+    ("Deref", "ops"),
+    ("DerefMut", "ops"),
+    ("Display", "fmt"),
+    ("Drop", "ops"),
+    ("Eq", "cmp"),
+    ("Error", "error"),
+    ("From", "convert"),
+    ("FromStr", "str"),
+    ("Hash", "hash"),
+    ("Into", "convert"),
+    ("IntoIterator", "iter"),
+    ("Iterator", "iter"),
+    ("Ord", "cmp"),
+    ("PartialEq", "cmp"),
+    ("PartialOrd", "cmp"),
+    ("Send", "marker"),
+    ("Sync", "marker"),
+    ("TryFrom", "convert"),
+    ("TryInto", "convert"),
+];
+
+/// Links the name of a trait of the standard library to its documentation. Other
+/// traits are returned unchanged.
+fn link_trait(trait_name: &str) -> String {
+    let name = trait_name.rsplit("::").next().unwrap_or(trait_name);
+    match STD_TRAIT_MODULES
+        .iter()
+        .find(|(std_name, _)| *std_name == name)
+    {
+        Some((_, module)) => {
+            format!("[{trait_name}](https://doc.rust-lang.org/std/{module}/trait.{name}.html)")
+        }
+        None => trait_name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::class_object::{ForeignMod, Trait, TraitImpl};
+
+    use super::*;
+
+    #[test]
+    fn test_render_class_with_fields_and_methods() {
+        let class = Class {
+            plantuml: String::from("@startuml\n@enduml"),
+            name: String::from("Person"),
+            documentation: String::from("A person.\n"),
+            fields: vec![Method {
+                name: String::from("name"),
+                documentation: String::from("The name.\n"),
+                ..Default::default()
+            }],
+            methods: vec![Method {
+                name: String::from("new(name: String)"),
+                documentation: String::from("Creates a person.\n"),
+                ..Default::default()
+            }],
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let expected_markdown = "## Person\n```plantuml\n@startuml\n@enduml\n```\n\nA person.\n\n\
+            \n### name\nThe name.\n\n\n### new(name: String)\nCreates a person.\n\n";
+
+        let markdown = MarkdownRenderer::default().render(&[class]);
+
+        assert_eq!(markdown, expected_markdown);
+    }
+
+    #[test]
+    fn test_render_class_with_constants_section() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Config"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constants: vec![Method {
+                name: String::from("MAX: usize = 100"),
+                documentation: String::from("The maximum.\n"),
+                ..Default::default()
+            }],
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+
+        let markdown = MarkdownRenderer::default().render(&[class]);
+
+        assert!(markdown.ends_with("\n## Constants\n\n### MAX: usize = 100\nThe maximum.\n\n"));
+    }
+
+    #[test]
+    fn test_render_class_with_reexports_section() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Rustitect"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: vec![Method {
+                name: String::from("model::Class as Model"),
+                documentation: String::from("The model.\n"),
+                visibility: Some(String::from("pub")),
+                ..Default::default()
+            }],
+            foreign_mods: Vec::new(),
+        };
+
+        let markdown = MarkdownRenderer::default().render(&[class]);
+
+        assert!(markdown
+            .ends_with("\n## Re-exports\n\n### pub use model::Class as Model\nThe model.\n\n"));
+    }
+
+    #[test]
+    fn test_render_class_with_diagram_fence() {
+        let class = Class {
+            plantuml: String::from("@startuml\n@enduml"),
+            name: String::from("Person"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let renderer = MarkdownRenderer {
+            diagram_fence: String::from("puml"),
+            ..Default::default()
+        };
+
+        let markdown = renderer.render(&[class]);
+
+        assert!(markdown.starts_with("## Person\n```puml\n@startuml\n@enduml\n```\n"));
+    }
+
+    #[test]
+    fn test_render_class_with_trait_implementations_section() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Person"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constants: Vec::new(),
+            trait_impls: vec![TraitImpl {
+                name: String::from("Identify"),
+                documentation: String::new(),
+                constants: vec![Method {
+                    name: String::from("ID: u32 = 5"),
+                    documentation: String::from("The identifier.\n"),
+                    ..Default::default()
+                }],
+                methods: vec![Method {
+                    name: String::from("id()"),
+                    documentation: String::new(),
+                    ..Default::default()
+                }],
+            }],
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+
+        let markdown = MarkdownRenderer::default().render(&[class]);
+
+        assert!(markdown.ends_with(
+            "\n## Trait Implementations\n\n### impl Identify\n\n\n#### ID: u32 = 5\nThe identifier.\n\n\n#### id()\n\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_class_with_traits_section() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::new(),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: vec![Trait {
+                name: String::from("Shape"),
+                documentation: String::from("A shape.\n"),
+                object_safe: false,
+                methods: vec![
+                    Method {
+                        name: String::from("create()"),
+                        documentation: String::from("Creates a shape.\n"),
+                        ..Default::default()
+                    },
+                    Method {
+                        name: String::from("describe()"),
+                        documentation: String::from("Describes the shape.\n"),
+                        provided: true,
+                        ..Default::default()
+                    },
+                ],
+            }],
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+
+        let markdown = MarkdownRenderer::default().render(&[class]);
+
+        assert!(markdown.ends_with(
+            "\n## Traits\n\n### trait Shape\nA shape.\n\n_Object-safe: no_\n\n#### create() (required)\nCreates a shape.\n\n\
+            \n#### describe() (default)\nDescribes the shape.\n\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_class_with_configured_headings() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Limits"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: vec![Method {
+                name: String::from("check()"),
+                documentation: String::from("Checks the limits.\n\n# Arguments\n\nNone.\n"),
+                ..Default::default()
+            }],
+            constants: vec![Method {
+                name: String::from("MAX: u32 = 5"),
+                documentation: String::new(),
+                ..Default::default()
+            }],
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let renderer = MarkdownRenderer {
+            headings: HeadingConfig {
+                level: 1,
+                constants: String::from("Konstanten"),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let markdown = renderer.render(&[class]);
+
+        assert_eq!(
+            markdown,
+            "# Limits\n\n\n\n## check()\nChecks the limits.\n\n### Arguments\n\nNone.\n\n\
+            \n# Konstanten\n\n## MAX: u32 = 5\n\n"
+        );
+    }
+
+    #[test]
+    fn test_render_class_with_methods_grouped_by_visibility() {
+        let method = |name: &str, visibility: &str| Method {
+            name: String::from(name),
+            documentation: String::new(),
+            visibility: Some(String::from(visibility)),
+            ..Default::default()
+        };
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Cache"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: vec![
+                method("evict()", "private"),
+                method("get()", "pub"),
+                method("resize()", "pub(crate)"),
+            ],
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let renderer = MarkdownRenderer {
+            group_by_visibility: true,
+            ..Default::default()
+        };
+
+        let markdown = renderer.render(&[class]);
+
+        assert!(markdown.ends_with(
+            "\n**Public API**\n\n### get()\n\n\n**Internal**\n\n### evict()\n\n\n### resize()\n\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_class_with_builder_methods_grouped() {
+        let method = |name: &str, builder: bool| Method {
+            name: String::from(name),
+            documentation: String::new(),
+            builder,
+            ..Default::default()
+        };
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Request"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: vec![
+                method("new()", true),
+                method("with_timeout(timeout: Duration)", true),
+                method("send()", false),
+            ],
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let renderer = MarkdownRenderer {
+            group_builders: true,
+            ..Default::default()
+        };
+
+        let markdown = renderer.render(&[class]);
+
+        assert!(markdown.ends_with(
+            "\n### send()\n\n\n### Builder Methods\n\n#### new()\n\n\n#### with_timeout(timeout: Duration)\n\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_class_with_unified_members() {
+        let member = |name: &str, line: usize| Method {
+            name: String::from(name),
+            documentation: format!("Doc of {name}.\n"),
+            line: Some(line),
+            ..Default::default()
+        };
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Person"),
+            documentation: String::new(),
+            fields: vec![member("name", 2), member("age", 9)],
+            methods: vec![member("new()", 5)],
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let renderer = MarkdownRenderer {
+            unified_members: true,
+            ..Default::default()
+        };
+
+        let markdown = renderer.render(&[class]);
+
+        assert!(markdown.ends_with(
+            "\n### Members\n\n#### name\n_Field_\n\nDoc of name.\n\n\
+            \n#### new()\n_Method_\n\nDoc of new().\n\n\
+            \n#### age\n_Field_\n\nDoc of age.\n\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_class_with_definition_lists() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Person"),
+            documentation: String::new(),
+            fields: vec![Method {
+                name: String::from("name"),
+                documentation: String::from("The name.\n\nIt is trimmed.\n"),
+                ..Default::default()
+            }],
+            methods: vec![Method {
+                name: String::from("greet()"),
+                documentation: String::from("Greets.\n"),
+                ..Default::default()
+            }],
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let renderer = MarkdownRenderer {
+            definition_lists: true,
+            ..Default::default()
+        };
+
+        let markdown = renderer.render(&[class]);
+
+        assert!(markdown
+            .ends_with("\nname\n:   The name.\n\n    It is trimmed.\n\ngreet()\n:   Greets.\n"));
+    }
+
+    #[test]
+    fn test_render_definition_lists_without_anchors_with_placeholder() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Person"),
+            documentation: String::new(),
+            fields: vec![Method {
+                name: String::from("name"),
+                ..Default::default()
+            }],
+            methods: vec![Method {
+                name: String::from("greet()"),
+                documentation: String::from("Greets.\n"),
+                ..Default::default()
+            }],
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let renderer = MarkdownRenderer {
+            definition_lists: true,
+            method_anchors: true,
+            ..Default::default()
+        };
+
+        let markdown = renderer.render(&[class]);
+
+        assert!(markdown.ends_with("\nname\n:   _Undocumented._\n\ngreet()\n:   Greets.\n"));
+        assert!(!markdown.contains("#Person_greet"));
+    }
+
+    #[test]
+    fn test_nest_headings_below_member_level() {
+        let documentation = "# Examples\n```rust\n# let hidden = 1;\n```\n## Details\n";
+
+        assert_eq!(
+            nest_headings(documentation, 4),
+            "#### Examples\n```rust\n# let hidden = 1;\n```\n##### Details\n"
+        );
+        assert_eq!(nest_headings("#### Returns\n", 4), "#### Returns\n");
+    }
+
+    #[test]
+    fn test_render_trait_implementations_links_std_traits() {
+        let trait_impl = |name: &str| TraitImpl {
+            name: String::from(name),
+            documentation: String::new(),
+            constants: Vec::new(),
+            methods: Vec::new(),
+        };
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Person"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constants: Vec::new(),
+            trait_impls: vec![trait_impl("fmt::Display"), trait_impl("Clone")],
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+
+        let markdown = MarkdownRenderer::default().render(&[class]);
+
+        assert!(markdown.contains(
+            "### impl [fmt::Display](https://doc.rust-lang.org/std/fmt/trait.Display.html)\n"
+        ));
+        assert!(markdown
+            .contains("### impl [Clone](https://doc.rust-lang.org/std/clone/trait.Clone.html)\n"));
+    }
+
+    #[test]
+    fn test_render_with_toc_links_to_sections() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Person"),
+            documentation: String::from("# Examples\n```\n## Not a heading\n```\n"),
+            fields: vec![Method {
+                name: String::from("name"),
+                documentation: String::new(),
+                ..Default::default()
+            }],
+            methods: vec![Method {
+                name: String::from("new(name: String)"),
+                documentation: String::from("# Examples\n"),
+                ..Default::default()
+            }],
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let renderer = MarkdownRenderer {
+            toc: true,
+            ..Default::default()
+        };
+
+        let markdown = renderer.render(&[class]);
+
+        assert!(markdown.starts_with(
+            "**Contents**\n\n- [Person](#person)\n  - [name](#name)\n  - [new(name: String)](#newname-string)\n\n## Person\n"
+        ));
+    }
+
+    #[test]
+    fn test_method_anchor_leaves_out_generic_parameters() {
+        assert_eq!(
+            method_anchor(
+                "Wrapper<T: Clone, const N: usize>",
+                "get(&self, index: usize)"
+            ),
+            "Wrapper_get"
+        );
+    }
+
+    #[test]
+    fn test_render_with_method_anchors_links_overview_to_sections() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Person"),
+            documentation: String::from("A person.\n"),
+            fields: Vec::new(),
+            methods: vec![Method {
+                name: String::from("introduce(greeting: &str)"),
+                documentation: String::from("Introduces the person.\n"),
+                ..Default::default()
+            }],
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+        let renderer = MarkdownRenderer {
+            method_anchors: true,
+            ..Default::default()
+        };
+
+        let markdown = renderer.render(&[class]);
+
+        assert!(markdown.contains(
+            "A person.\n\n\n**Methods**\n\n- [introduce(greeting: &str)](#Person_introduce)\n"
+        ));
+        assert!(markdown.contains("\n### introduce(greeting: &str) {#Person_introduce}\n"));
+    }
+
+    #[test]
+    fn test_render_class_with_foreign_section() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Library"),
+            documentation: String::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: vec![ForeignMod {
+                abi: String::from("C"),
+                items: vec![Method {
+                    name: String::from("checksum(length: usize) -> u32"),
+                    documentation: String::from("Computes the checksum.\n"),
+                    ..Default::default()
+                }],
+            }],
+        };
+
+        let markdown = MarkdownRenderer::default().render(&[class]);
+
+        assert!(markdown.ends_with(
+            "\n## Foreign (extern \"C\")\n\n### checksum(length: usize) -> u32\nComputes the checksum.\n\n"
+        ));
+    }
+}
@@ -0,0 +1,17 @@
+use crate::model::class_object::Class;
+use crate::renderer::Renderer;
+
+/// Renders the PlantUML diagrams of the classes, which were already generated
+/// while parsing the input.
+#[derive(Default)]
+pub struct PlantumlRenderer {}
+
+impl Renderer for PlantumlRenderer {
+    fn render(&self, classes: &[Class]) -> String {
+        classes
+            .iter()
+            .map(|class| class.plantuml.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,63 @@
+use serde::Serialize;
+
+use crate::model::class_object::Class;
+use crate::renderer::Renderer;
+
+/// Renders classes to TOML with the same structure as the JSON output.
+///
+/// A single class is rendered as top level table. As TOML has no top level
+/// arrays, multiple classes are rendered as array of tables named `classes`.
+/// The members are ordered like in the JSON output.
+#[derive(Default)]
+pub struct TomlRenderer {}
+
+/// The top level table of multiple classes.
+#[derive(Serialize)]
+struct Classes<'a> {
+    classes: &'a [Class],
+}
+
+impl Renderer for TomlRenderer {
+    fn render(&self, classes: &[Class]) -> String {
+        let toml = match classes {
+            [class] => toml::to_string(class),
+            _ => toml::to_string(&Classes { classes }),
+        };
+        toml.expect("Failed to serialize to TOML")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::class_object::Method;
+
+    #[test]
+    fn test_render_class_to_toml() {
+        let class = Class {
+            plantuml: String::new(),
+            name: String::from("Person"),
+            documentation: String::from("A person.\n"),
+            fields: vec![Method {
+                name: String::from("name"),
+                documentation: String::from("The name.\n"),
+                line: Some(3),
+                ..Default::default()
+            }],
+            methods: Vec::new(),
+            constants: Vec::new(),
+            trait_impls: Vec::new(),
+            traits: Vec::new(),
+            reexports: Vec::new(),
+            foreign_mods: Vec::new(),
+        };
+
+        let toml = TomlRenderer::default().render(&[class]);
+
+        let parsed: toml::Table = toml.parse().unwrap();
+        assert_eq!(parsed["name"].as_str(), Some("Person"));
+        assert_eq!(parsed["fields"][0]["name"].as_str(), Some("name"));
+        assert_eq!(parsed["fields"][0]["line"].as_integer(), Some(3));
+        assert_eq!(parsed["methods"].as_array().map(Vec::len), Some(0));
+    }
+}
@@ -40,6 +40,7 @@ fn test_main_asciidoc_output_is_correct() {
     let expected_output = read_file_content_to_string(&file_path);
 
     let output = Command::new(path)
+        .args(["--document-private-items"])
         .args(input_file_path.to_str())
         .output()
         .expect("Failed to execute command");
@@ -101,6 +102,35 @@ fn test_main_preserve_name_only_with_input_file() {
     assert_eq!(output.status.code(), Some(101));
 }
 
+#[test]
+fn test_preserve_names_with_stdin_name() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let output_dir = Path::new(&manifest_dir).join("target/test_output/stdin_name");
+
+    let mut child = Command::new(path)
+        .args(["--preserve-names", "--input-stdin-name", "piped"])
+        .args(["--format", "markdown"])
+        .args(["--output-dir", output_dir.to_str().unwrap()])
+        .stdin(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"/// A piped struct.\nstruct Piped {}\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    let markdown = read_file_content_to_string(&output_dir.join("piped.md"));
+    assert!(markdown.contains("A piped struct."));
+
+    std::fs::remove_dir_all(output_dir).unwrap();
+}
+
 #[test]
 fn test_asciidoc_plantuml_format_generates_two_files() {
     let path = path_of_project_exe();
@@ -115,7 +145,7 @@ fn test_asciidoc_plantuml_format_generates_two_files() {
     let expected_plantuml = read_file_content_to_string(&expected_file_puml);
 
     let output = Command::new(path)
-        .args(["--preserve-names"])
+        .args(["--preserve-names", "--document-private-items"])
         .args(["--format", "asciidoc-plantuml"])
         .args(input_file_path.to_str())
         .output()
@@ -138,6 +168,290 @@ fn test_asciidoc_plantuml_format_generates_two_files() {
     std::fs::remove_file(expected_output_file_puml).unwrap();
 }
 
+#[test]
+fn test_output_dir_include_is_relative_to_working_directory() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+    let output_dir = "target/test_output/cwd_includes/docs";
+    let expected_output_file_adoc = Path::new(&manifest_dir)
+        .join(output_dir)
+        .join("simple_struct.adoc");
+    let expected_include = format!("plantuml::{output_dir}/simple_struct.puml[]");
+
+    let output = Command::new(path)
+        .current_dir(&manifest_dir)
+        .args(["--preserve-names"])
+        .args(["--format", "asciidoc-plantuml"])
+        .args(["--output-dir", output_dir])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let asciidoc = read_file_content_to_string(&expected_output_file_adoc);
+    assert!(asciidoc.contains(&expected_include));
+
+    std::fs::remove_dir_all(Path::new(&manifest_dir).join(output_dir)).unwrap();
+}
+
+#[test]
+fn test_relative_includes_in_nested_output_dir() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+    let output_dir = "target/test_output/relative_includes/nested/docs";
+    let expected_output_file_adoc = Path::new(&manifest_dir)
+        .join(output_dir)
+        .join("simple_struct.adoc");
+    let expected_output_file_puml = Path::new(&manifest_dir)
+        .join(output_dir)
+        .join("simple_struct.puml");
+
+    let output = Command::new(path)
+        .current_dir(&manifest_dir)
+        .args(["--preserve-names", "--relative-includes"])
+        .args(["--format", "asciidoc-plantuml"])
+        .args(["--output-dir", output_dir])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    assert!(expected_output_file_puml.exists());
+    let asciidoc = read_file_content_to_string(&expected_output_file_adoc);
+    assert!(asciidoc.contains("plantuml::simple_struct.puml[]"));
+
+    std::fs::remove_dir_all(Path::new(&manifest_dir).join(output_dir)).unwrap();
+}
+
+#[test]
+fn test_includes_relative_to_base_dir() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+    let output_dir = "target/test_output/base_dir/docs/classes";
+    let base_dir = "target/test_output/base_dir/docs";
+    let expected_output_file_adoc = Path::new(&manifest_dir)
+        .join(output_dir)
+        .join("simple_struct.adoc");
+
+    let output = Command::new(path)
+        .current_dir(&manifest_dir)
+        .args(["--preserve-names", "--format", "asciidoc-plantuml"])
+        .args(["--output-dir", output_dir, "--base-dir", base_dir])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let asciidoc = read_file_content_to_string(&expected_output_file_adoc);
+    assert!(asciidoc.contains("plantuml::classes/simple_struct.puml[]"));
+
+    std::fs::remove_dir_all(Path::new(&manifest_dir).join("target/test_output/base_dir")).unwrap();
+}
+
+#[test]
+fn test_name_pattern_with_placeholders() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+    let output_dir = "target/test_output/name_pattern";
+
+    let output = Command::new(&path)
+        .current_dir(&manifest_dir)
+        .args(["--preserve-names", "--format", "asciidoc-plantuml"])
+        .args(["--name-pattern", "{parent}_{stem}_docs{ext}"])
+        .args(["--output-dir", output_dir, "--relative-includes"])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+    let unknown_placeholder_output = Command::new(&path)
+        .args(["--name-pattern", "{name}{ext}"])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let output_dir = Path::new(&manifest_dir).join(output_dir);
+    let asciidoc =
+        read_file_content_to_string(&output_dir.join("resources_simple_struct_docs.adoc"));
+    assert!(asciidoc.contains("plantuml::resources_simple_struct_docs.puml[]"));
+    assert!(output_dir
+        .join("resources_simple_struct_docs.puml")
+        .exists());
+    assert!(!unknown_placeholder_output.status.success());
+    assert!(String::from_utf8_lossy(&unknown_placeholder_output.stderr)
+        .contains("Unknown placeholder '{name}'"));
+
+    std::fs::remove_dir_all(output_dir).unwrap();
+}
+
+#[test]
+fn test_validate_includes_resolves_the_prefixed_file() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+    let output_dir = "target/test_output/validate_includes";
+
+    let valid_output = Command::new(&path)
+        .current_dir(&manifest_dir)
+        .args(["--preserve-names", "--format", "asciidoc-plantuml"])
+        .args(["--output-dir", output_dir, "--validate-includes"])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+    let prefixed_output = Command::new(&path)
+        .current_dir(&manifest_dir)
+        .args(["--preserve-names", "--format", "asciidoc-plantuml"])
+        .args(["--prefix", "x_"])
+        .args(["--output-dir", output_dir, "--validate-includes"])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+    let asciidoc = std::fs::read_to_string(
+        Path::new(&manifest_dir).join(format!("{output_dir}/x_simple_struct.adoc")),
+    )
+    .unwrap();
+
+    assert!(valid_output.status.success());
+    assert!(prefixed_output.status.success());
+    assert!(asciidoc.contains(&format!("plantuml::{output_dir}/x_simple_struct.puml[]")));
+
+    std::fs::remove_dir_all(Path::new(&manifest_dir).join(output_dir)).unwrap();
+}
+
+#[test]
+fn test_name_pattern_without_extension_is_rejected_for_two_files() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+    let output_dir = "target/test_output/colliding_name_pattern";
+
+    let output = Command::new(&path)
+        .current_dir(&manifest_dir)
+        .args(["--preserve-names", "--format", "asciidoc-plantuml"])
+        .args(["--name-pattern", "{stem}.txt"])
+        .args(["--output-dir", output_dir])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'simple_struct.txt'"));
+    assert!(!Path::new(&manifest_dir)
+        .join(output_dir)
+        .join("simple_struct.txt")
+        .exists());
+}
+
+#[test]
+fn test_output_format_from_extension() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+    let output_dir = "target/test_output/format_from_extension";
+
+    let output = Command::new(&path)
+        .current_dir(&manifest_dir)
+        .args(["--output-format-from-extension", "--output-dir", output_dir])
+        .args(["--output-file", "out.md"])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+    let explicit_format_output = Command::new(&path)
+        .current_dir(&manifest_dir)
+        .args(["--output-format-from-extension", "--output-dir", output_dir])
+        .args(["--output-file", "explicit.md", "--format", "json"])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    assert!(explicit_format_output.status.success());
+    let output_dir = Path::new(&manifest_dir).join(output_dir);
+    let markdown = read_file_content_to_string(&output_dir.join("out.md"));
+    assert!(markdown.starts_with("## Person\n"));
+    assert!(!output_dir.join("out.adoc").exists());
+    assert!(output_dir.join("explicit.json").exists());
+
+    std::fs::remove_dir_all(output_dir).unwrap();
+}
+
+#[test]
+fn test_files_from_stdin_documents_only_the_listed_files() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let test_dir = Path::new(&manifest_dir).join("target/test_output/files_from");
+    let input_dir = test_dir.join("src");
+    let output_dir = test_dir.join("docs");
+    std::fs::create_dir_all(&input_dir).unwrap();
+    std::fs::write(
+        input_dir.join("changed.rs"),
+        "/// Changed.\nstruct Changed {}\n",
+    )
+    .unwrap();
+    std::fs::write(input_dir.join("unchanged.rs"), "struct Unchanged {}\n").unwrap();
+    std::fs::write(input_dir.join("notes.txt"), "Not Rust.\n").unwrap();
+
+    let mut child = Command::new(path)
+        .current_dir(&test_dir)
+        .args(["--files-from", "-", "--format", "markdown"])
+        .args(["--output-dir", "docs"])
+        .stdin(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"src/changed.rs\nsrc/notes.txt\nsrc/deleted.rs\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    let markdown = read_file_content_to_string(&output_dir.join("changed.md"));
+    assert!(markdown.contains("Changed."));
+    assert!(!output_dir.join("unchanged.md").exists());
+    assert!(!output_dir.join("notes.md").exists());
+
+    std::fs::remove_dir_all(test_dir).unwrap();
+}
+
+#[test]
+fn test_per_method_diagrams_follow_name_pattern() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+    let output_dir = "target/test_output/per_method_name_pattern";
+
+    let output = Command::new(&path)
+        .current_dir(&manifest_dir)
+        .args([
+            "--preserve-names",
+            "--format",
+            "markdown",
+            "--per-method-diagrams",
+        ])
+        .args(["--name-pattern", "{parent}_{stem}_{format}{ext}"])
+        .args(["--output-dir", output_dir])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let output_dir = Path::new(&manifest_dir).join(output_dir);
+    assert!(output_dir
+        .join("resources_simple_struct_markdown.md")
+        .exists());
+    let diagram =
+        read_file_content_to_string(&output_dir.join("resources_simple_struct_plantuml_1.puml"));
+    assert!(diagram.contains("<b>new("));
+
+    std::fs::remove_dir_all(output_dir).unwrap();
+}
+
 fn path_of_project_exe() -> PathBuf {
     let cargo_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
     let project_name = std::env::var("CARGO_PKG_NAME").unwrap();
@@ -170,3 +484,311 @@ fn test_file_name_prefix() {
 
     std::fs::remove_file(expected_output_file).unwrap();
 }
+
+#[test]
+fn test_diff_against_previous_output() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+    let output_dir = Path::new(&manifest_dir).join("target/test_output/diff");
+    let old_file_path = output_dir.join("simple_struct.md");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    let mut old_file = File::create(&old_file_path).unwrap();
+    old_file.write_all(b"obsolete line\n").unwrap();
+
+    let output = Command::new(path)
+        .args(["--format", "markdown"])
+        .args(["--diff", old_file_path.to_str().unwrap()])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    let output_as_string = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(output_as_string.contains("+++ generated"));
+    assert!(output_as_string.contains("-obsolete line"));
+    assert!(output_as_string.contains("+## "));
+
+    std::fs::remove_dir_all(output_dir).unwrap();
+}
+
+#[test]
+fn test_diff_compares_the_output_of_an_only_flag() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+    let output_dir = Path::new(&manifest_dir).join("target/test_output/diff_only_flag");
+    let old_file_path = output_dir.join("simple_struct.md");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    std::fs::write(&old_file_path, "obsolete line\n").unwrap();
+
+    let output = Command::new(path)
+        .args(["--markdown-only"])
+        .args(["--diff", old_file_path.to_str().unwrap()])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    let output_as_string = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(output_as_string.contains("-obsolete line"));
+    assert!(output_as_string.contains("+## Person"));
+
+    std::fs::remove_dir_all(output_dir).unwrap();
+}
+
+#[test]
+fn test_strict_fails_on_warnings() {
+    let path = path_of_project_exe();
+    let input = "struct Component { name: String }\nyew::html! { <Component /> }\n";
+
+    let mut child = Command::new(path)
+        .args(["--format", "markdown", "--strict"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    let stderr_as_string = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr_as_string.contains("warning: Skipped macro invocation 'yew::html!'"));
+}
+
+#[test]
+fn test_strict_succeeds_without_warnings() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+
+    let output = Command::new(path)
+        .args(["--format", "markdown", "--strict"])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_summary_is_printed_unless_quiet() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+
+    let output = Command::new(&path)
+        .args(["--format", "markdown"])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+    let quiet_output = Command::new(&path)
+        .args(["--format", "markdown", "--quiet"])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    let stderr_as_string = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr_as_string.contains("Processed 1 file(s): 1 type(s), "));
+    assert!(stderr_as_string.contains(", 0 output file(s) written"));
+    assert!(quiet_output.stderr.is_empty());
+}
+
+#[test]
+fn test_module_diagrams_of_a_directory() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_dir = Path::new(&manifest_dir).join("target/test_output/module_diagrams/src");
+    let output_dir = Path::new(&manifest_dir).join("target/test_output/module_diagrams/docs");
+    std::fs::create_dir_all(&input_dir).unwrap();
+    std::fs::write(
+        input_dir.join("orders.rs"),
+        "struct Order { id: u32 }\nstruct Customer { id: u32 }\n",
+    )
+    .unwrap();
+    std::fs::write(input_dir.join("limits.rs"), "const LIMIT: u32 = 10;\n").unwrap();
+
+    let output = Command::new(path)
+        .args(["--format", "markdown", "--module-diagrams"])
+        .args(["--output-dir", output_dir.to_str().unwrap()])
+        .args(input_dir.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let diagram = read_file_content_to_string(&output_dir.join("orders.puml"));
+    assert!(diagram.contains("class \"Order\""));
+    assert!(diagram.contains("class \"Customer\""));
+    assert!(!output_dir.join("limits.puml").exists());
+
+    std::fs::remove_dir_all(Path::new(&manifest_dir).join("target/test_output/module_diagrams"))
+        .unwrap();
+}
+
+#[test]
+fn test_keep_going_documents_the_other_files_of_a_directory() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_dir = Path::new(&manifest_dir).join("target/test_output/keep_going/src");
+    let output_dir = Path::new(&manifest_dir).join("target/test_output/keep_going/docs");
+    std::fs::create_dir_all(&input_dir).unwrap();
+    std::fs::write(
+        input_dir.join("good.rs"),
+        "/// A good struct.\nstruct Good {}\n",
+    )
+    .unwrap();
+    std::fs::write(input_dir.join("broken.rs"), "struct Broken {\n").unwrap();
+
+    let output = Command::new(path)
+        .args(["--format", "markdown", "--keep-going"])
+        .args(["--output-dir", output_dir.to_str().unwrap()])
+        .args(input_dir.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    let stderr_as_string = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr_as_string.contains("broken.rs"));
+    assert!(output_dir.join("good.md").exists());
+    assert!(!output_dir.join("broken.md").exists());
+
+    std::fs::remove_dir_all(Path::new(&manifest_dir).join("target/test_output/keep_going"))
+        .unwrap();
+}
+
+#[test]
+fn test_strict_keep_going_documents_all_files_of_a_directory() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_dir = Path::new(&manifest_dir).join("target/test_output/strict_keep_going/src");
+    let output_dir = Path::new(&manifest_dir).join("target/test_output/strict_keep_going/docs");
+    std::fs::create_dir_all(&input_dir).unwrap();
+    for name in ["first", "second"] {
+        std::fs::write(
+            input_dir.join(format!("{name}.rs")),
+            "struct Component { name: String }\nyew::html! { <Component /> }\n",
+        )
+        .unwrap();
+    }
+
+    let output = Command::new(path)
+        .args(["--format", "markdown", "--strict", "--keep-going"])
+        .args(["--output-dir", output_dir.to_str().unwrap()])
+        .args(input_dir.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    let stderr_as_string = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr_as_string.contains("2 file(s) of"));
+    assert!(output_dir.join("first.md").exists());
+    assert!(output_dir.join("second.md").exists());
+
+    std::fs::remove_dir_all(Path::new(&manifest_dir).join("target/test_output/strict_keep_going"))
+        .unwrap();
+}
+
+#[test]
+fn test_single_input_options_are_rejected_for_a_directory() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_dir = Path::new(&manifest_dir).join("tests/resources");
+
+    let output = Command::new(path)
+        .args(["--format", "markdown", "--glossary=glossary.adoc"])
+        .args(input_dir.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--glossary requires a single input"));
+}
+
+#[test]
+fn test_files_from_rejects_files_documented_into_the_same_output_file() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let test_dir = Path::new(&manifest_dir).join("target/test_output/files_from_collision");
+    for module in ["a", "b"] {
+        std::fs::create_dir_all(test_dir.join("src").join(module)).unwrap();
+        std::fs::write(
+            test_dir.join("src").join(module).join("mod.rs"),
+            "struct Module {}\n",
+        )
+        .unwrap();
+    }
+    let run = |name_pattern: &str| {
+        let mut child = Command::new(&path)
+            .current_dir(&test_dir)
+            .args(["--files-from", "-", "--format", "markdown"])
+            .args(["--output-dir", "docs", "--name-pattern", name_pattern])
+            .stdin(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .expect("Failed to execute command");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"src/a/mod.rs\nsrc/b/mod.rs\n")
+            .unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    let colliding_output = run("{stem}{ext}");
+    let distinct_output = run("{parent}_{stem}{ext}");
+
+    assert_eq!(colliding_output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&colliding_output.stderr).contains("'mod.md'"));
+    assert!(!test_dir.join("docs/mod.md").exists());
+    assert!(distinct_output.status.success());
+    assert!(test_dir.join("docs/a_mod.md").exists());
+    assert!(test_dir.join("docs/b_mod.md").exists());
+
+    std::fs::remove_dir_all(test_dir).unwrap();
+}
+
+#[test]
+fn test_unified_members_conflicts_with_sorting_and_grouping() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+
+    for option in [
+        "--sort-fields=alphabetical",
+        "--group-by-visibility",
+        "--definition-lists",
+    ] {
+        let output = Command::new(&path)
+            .args(["--format", "markdown", "--unified-members", option])
+            .args(input_file_path.to_str())
+            .output()
+            .expect("Failed to execute command");
+
+        assert_eq!(output.status.code(), Some(2), "{option}");
+    }
+}
+
+#[test]
+fn test_asciidoc_plantuml_format_on_stdout_keeps_the_include_placeholder() {
+    let path = path_of_project_exe();
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input_file_path = Path::new(&manifest_dir).join("tests/resources/simple_struct.rs");
+
+    let output = Command::new(path)
+        .args(["--format", "asciidoc-plantuml"])
+        .args(input_file_path.to_str())
+        .output()
+        .expect("Failed to execute command");
+
+    let output_as_string = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(output_as_string.contains("\nplantuml::FILENAME.puml[]\n"));
+    assert!(!output_as_string.contains("simple_struct.puml"));
+}